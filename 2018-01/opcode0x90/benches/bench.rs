@@ -1,23 +1,60 @@
 #[macro_use]
 extern crate criterion;
-extern crate aoc;
+extern crate aoc_2018_01;
+extern crate aoc_bench;
+extern crate rand;
 
-use aoc::*;
-use criterion::Criterion;
+mod synthetic;
 
-fn bench_part1(c: &mut Criterion) {
-    c.bench_function("part1", |b| {
-        let input = get_input().unwrap();
-        b.iter(|| part1(&input))
-    });
+use aoc_2018_01::{get_input, part1_checked, part1_streaming, part2, part2_simulate, Day01};
+use aoc_bench::{bench, bench_synthetic};
+use criterion::{Criterion, ParameterizedBenchmark};
+
+fn bench_day01(c: &mut Criterion) {
+    bench::<Day01>(c);
+}
+
+fn bench_day01_synthetic(c: &mut Criterion) {
+    bench_synthetic::<Day01>(c, &[1, 10, 100], synthetic::generate);
 }
 
-fn bench_part2(c: &mut Criterion) {
-    c.bench_function("part2", |b| {
-        let input = get_input().unwrap();
-        b.iter(|| part2(&input))
-    });
+fn bench_part2_adversarial(c: &mut Criterion) {
+    let benchmark = ParameterizedBenchmark::new(
+        "simulate",
+        |b, &n| {
+            let input = synthetic::adversarial(n);
+            b.iter(|| part2_simulate(&input).unwrap())
+        },
+        vec![10, 100, 1000],
+    ).with_function("analytic", |b, &n| {
+        let input = synthetic::adversarial(n);
+        b.iter(|| part2(&input).unwrap())
+    }).sample_size(10);
+
+    c.bench("2018-01/adversarial", benchmark);
+}
+
+fn bench_part1_streaming(c: &mut Criterion) {
+    let benchmark = ParameterizedBenchmark::new(
+        "buffered",
+        |b, &scale| {
+            let data = synthetic::generate(scale).into_bytes();
+            b.iter(|| part1_checked(&get_input::<i64>(&data[..]).unwrap()).unwrap())
+        },
+        vec![1, 10, 100],
+    ).with_function("streaming", |b, &scale| {
+        let data = synthetic::generate(scale).into_bytes();
+        b.iter(|| part1_streaming::<i64>(&data[..]).unwrap())
+    }).sample_size(10);
+
+    c.bench("2018-01/part1", benchmark);
 }
 
-criterion_group!(benches, bench_part1, bench_part2);
+criterion_group!(
+    benches,
+    bench_day01,
+    bench_day01_synthetic,
+    bench_part2_adversarial,
+    bench_part1_streaming
+);
 criterion_main!(benches);
@@ -0,0 +1,34 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// number of frequency changes in input.txt
+const CHANGES: usize = 1004;
+
+/// generate `scale` times as many frequency changes as input.txt
+///
+/// the changes are all positive except the last one, which brings the
+/// frequency back to zero, so part2 has to go through one full pass
+pub fn generate(scale: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(2018);
+    let mut data = String::new();
+    let mut sum: i64 = 0;
+
+    for _ in 1..CHANGES * scale {
+        let x = rng.gen_range(1, 20);
+        data.push_str(&format!("+{}\n", x));
+        sum += x;
+    }
+    data.push_str(&format!("-{}\n", sum));
+    data
+}
+
+/// generate `n` frequency changes that only repeat after about `n` passes
+///
+/// the frequencies of the first pass are spread `n` apart with a drift of
+/// one, so the simulation has to go through about `n * n` changes
+pub fn adversarial(n: usize) -> Vec<i32> {
+    let step = n as i32;
+    let mut input = vec![step; n - 1];
+    input.push(1 - step * (n as i32 - 1));
+    input
+}
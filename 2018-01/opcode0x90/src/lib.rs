@@ -1,59 +1,550 @@
+extern crate aoc_common;
+
+use std::any;
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::convert::TryFrom;
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+use std::io::{BufRead, BufReader, Read};
+use std::iter::Sum;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use aoc_common::{Error, Solution};
+
+/// integer type used to accumulate the frequency
+pub trait Frequency:
+    Copy + Ord + Hash + Debug + Display + Sum + FromStr<Err = ParseIntError>
+{
+    const ZERO: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn checked_neg(self) -> Option<Self>;
+    fn checked_rem_euclid(self, rhs: Self) -> Option<Self>;
+    fn to_usize(self) -> Option<usize>;
+}
+
+macro_rules! impl_frequency {
+    ($($t:ty)*) => {$(
+        impl Frequency for $t {
+            const ZERO: $t = 0;
+
+            fn checked_add(self, rhs: $t) -> Option<$t> {
+                <$t>::checked_add(self, rhs)
+            }
+            fn checked_sub(self, rhs: $t) -> Option<$t> {
+                <$t>::checked_sub(self, rhs)
+            }
+            fn checked_div(self, rhs: $t) -> Option<$t> {
+                <$t>::checked_div(self, rhs)
+            }
+            fn checked_neg(self) -> Option<$t> {
+                <$t>::checked_neg(self)
+            }
+            fn checked_rem_euclid(self, rhs: $t) -> Option<$t> {
+                <$t>::checked_rem_euclid(self, rhs)
+            }
+            fn to_usize(self) -> Option<usize> {
+                usize::try_from(self).ok()
+            }
+        }
+    )*};
+}
 
-pub fn part1(input: &Vec<i32>) -> i32 {
+impl_frequency!(i32 i64 i128);
+
+/// overflow of the given type
+fn overflow<T>() -> Error {
+    Error::Overflow(any::type_name::<T>())
+}
+
+/// where the first frequency reached twice was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repeat<T> {
+    /// the first frequency reached twice
+    pub frequency: T,
+    /// number of full passes over the list before reaching it again
+    pub passes: usize,
+    /// position in the list of the change that reached it again
+    pub index: usize,
+    /// number of changes applied when it was first reached, 0 being the start
+    pub first_seen: usize,
+}
+
+impl<T> Repeat<T> {
+    /// build from the number of changes applied when each frequency was reached
+    fn new(frequency: T, len: usize, seen: usize, first_seen: usize) -> Repeat<T> {
+        Repeat {
+            frequency,
+            passes: (seen - 1) / len,
+            index: (seen - 1) % len,
+            first_seen,
+        }
+    }
+}
+
+impl<T: Display> fmt::Display for Repeat<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "frequency {} is reached again by change #{} after {} full passes, first seen after {} changes",
+            self.frequency, self.index, self.passes, self.first_seen
+        )
+    }
+}
+
+pub fn part1<T: Frequency>(input: &[T]) -> T {
     // compute the sum
-    input.iter().sum()
+    input.iter().copied().sum()
+}
+
+/// compute the sum, reporting overflow as an error
+pub fn part1_checked<T: Frequency>(input: &[T]) -> Result<T, Error> {
+    input
+        .iter()
+        .try_fold(T::ZERO, |acc, &x| acc.checked_add(x).ok_or_else(overflow::<T>))
+}
+
+/// check if any frequency will ever be reached twice, without simulating
+fn repeats<T: Frequency>(input: &[T]) -> Result<bool, Error> {
+    // compute the drift after each pass
+    let drift = part1_checked(input)?;
+
+    if input.is_empty() {
+        return Ok(false);
+    }
+    if drift == T::ZERO {
+        // back to where it started after every pass
+        return Ok(true);
+    }
+
+    // frequencies visited on each pass are the previous ones shifted by drift,
+    // so two frequencies can only meet if they are congruent modulo drift
+    let mut residues = HashSet::new();
+    let mut acc = T::ZERO;
+
+    for &x in input {
+        let residue = acc.checked_rem_euclid(drift).ok_or_else(overflow::<T>)?;
+        if !residues.insert(residue) {
+            return Ok(true);
+        }
+        acc = acc.checked_add(x).ok_or_else(overflow::<T>)?;
+    }
+    Ok(false)
 }
 
-pub fn part2(input: &Vec<i32>) -> i32 {
+/// find the first frequency reached twice by simulating pass after pass
+pub fn part2_simulate<T: Frequency>(input: &[T]) -> Result<Option<T>, Error> {
+    // bail out if the simulation below would never terminate
+    if !repeats(input)? {
+        return Ok(None);
+    }
+
     let mut delta = HashSet::new();
-    let mut acc = 0;
+    let mut acc = T::ZERO;
 
-    for x in input.iter().cycle() {
+    for &x in input.iter().cycle() {
         // attempt to store each accumulator into HashSet
         // any duplicate means we found the solution
         if !delta.insert(acc) {
             break;
         }
-        acc += x;
+        acc = acc.checked_add(x).ok_or_else(overflow::<T>)?;
+    }
+    Ok(Some(acc))
+}
+
+/// find the first frequency reached twice without simulating, in O(n log n)
+///
+/// every pass visits the frequencies of the first pass shifted by the drift,
+/// so frequency `a` reaches frequency `b` after `(b - a) / drift` passes if
+/// both are congruent modulo drift. after sorting the first pass by residue
+/// and frequency, only the next frequency within each group has to be checked.
+pub fn find_repeat<T: Frequency>(input: &[T]) -> Result<Option<Repeat<T>>, Error> {
+    let n = input.len();
+    let drift = part1_checked(input)?;
+
+    // mirror the frequencies when drifting downwards, so they always go up
+    let mirrored = drift < T::ZERO;
+    let mirror = |f: T| {
+        if mirrored {
+            f.checked_neg().ok_or_else(overflow::<T>)
+        } else {
+            Ok(f)
+        }
+    };
+    let drift = mirror(drift)?;
+
+    // frequencies before each change of the first pass, as (residue, frequency, index)
+    let mut acc = T::ZERO;
+    let mut freq = Vec::with_capacity(n);
+
+    for (i, &x) in input.iter().enumerate() {
+        let f = mirror(acc)?;
+        let residue = if drift == T::ZERO {
+            T::ZERO
+        } else {
+            f.checked_rem_euclid(drift).ok_or_else(overflow::<T>)?
+        };
+        freq.push((residue, f, i));
+        acc = acc.checked_add(x).ok_or_else(overflow::<T>)?;
+    }
+    freq.sort();
+
+    // every repeat as (number of changes applied, frequency, when it was first seen)
+    let candidates = freq.windows(2).filter_map(|pair| {
+        let ((r1, f1, i1), (r2, f2, i2)) = (pair[0], pair[1]);
+
+        if r1 != r2 {
+            // never meets
+            None
+        } else if f1 == f2 {
+            // reached twice within the first pass
+            Some(Ok((i2, f2, i1)))
+        } else if drift > T::ZERO {
+            // f1 catches up with f2 after some passes
+            let seen = f2
+                .checked_sub(f1)
+                .and_then(|distance| distance.checked_div(drift))
+                .and_then(T::to_usize)
+                .and_then(|passes| passes.checked_mul(n))
+                .and_then(|changes| changes.checked_add(i1))
+                .ok_or_else(overflow::<usize>);
+            Some(seen.map(|seen| (seen, f2, i2)))
+        } else {
+            None
+        }
+    });
+
+    // without drift, it is back to where it started after one pass
+    let restart = if drift == T::ZERO && n > 0 {
+        Some(Ok((n, T::ZERO, 0)))
+    } else {
+        None
+    };
+
+    // pick the earliest one
+    let candidates = candidates.chain(restart).collect::<Result<Vec<_>, _>>()?;
+
+    match candidates.into_iter().min() {
+        Some((seen, f, first_seen)) => Ok(Some(Repeat::new(mirror(f)?, n, seen, first_seen))),
+        None => Ok(None),
     }
-    acc
 }
 
-pub fn get_input() -> Result<Vec<i32>, Box<std::error::Error>> {
+pub fn part2<T: Frequency>(input: &[T]) -> Result<Option<T>, Error> {
+    Ok(find_repeat(input)?.map(|repeat| repeat.frequency))
+}
+
+/// parse every frequency change on given line, `n` is 1-based
+fn parse_line<T: Frequency>(n: usize, line: &str) -> impl Iterator<Item = Result<T, Error>> + '_ {
+    // changes are separated by commas and/or whitespace, the `+` sign is optional
+    line.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .map(move |token| {
+            token
+                .parse::<T>()
+                .map_err(|err| Error::parse_int(n, token, err))
+        })
+}
+
+pub fn get_input<T: Frequency>(f: impl Read) -> Result<Vec<T>, Error> {
     // read data from input.txt
-    let f = File::open("input.txt").expect("input.txt not found!");
-    let input = BufReader::new(f).lines().flatten();
+    let input = BufReader::new(f).lines().collect::<Result<Vec<_>, _>>()?;
 
     // parse the input into integers
     let parsed = input
-        .into_iter()
-        .map(|line| line.parse::<i32>())
+        .iter()
+        .enumerate()
+        .flat_map(|(n, line)| parse_line(n + 1, line))
         .collect::<Result<Vec<_>, _>>()?;
 
     Ok(parsed)
 }
 
+/// compute the sum while reading, without keeping the changes in memory
+///
+/// only part1 can be streamed, part2 has to go through the changes more than once.
+pub fn part1_streaming<T: Frequency>(mut f: impl BufRead) -> Result<T, Error> {
+    let mut line = String::new();
+    let mut acc = T::ZERO;
+    let mut n = 0;
+
+    // reuse the same buffer for every line
+    while f.read_line(&mut line)? > 0 {
+        n += 1;
+        for x in parse_line(n, &line) {
+            acc = acc.checked_add(x?).ok_or_else(overflow::<T>)?;
+        }
+        line.clear();
+    }
+    Ok(acc)
+}
+
+const NO_REPEAT: Error = Error::NoSolution("no frequency is ever reached twice");
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const YEAR: u32 = 2018;
+    const DAY: u32 = 1;
+
+    type Input = Vec<i64>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(f: impl Read) -> Result<Vec<i64>, Error> {
+        get_input(f)
+    }
+
+    fn part1(input: &Vec<i64>) -> Result<i64, Error> {
+        part1_checked(input)
+    }
+
+    fn part2(input: &Vec<i64>) -> Result<i64, Error> {
+        part2(input)?.ok_or(NO_REPEAT)
+    }
+
+    fn details(input: &Vec<i64>) -> Result<Option<String>, Error> {
+        let repeat = find_repeat(input)?.ok_or(NO_REPEAT)?;
+        Ok(Some(repeat.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    /// naive reference implementation of find_repeat, linear search on every step
+    fn naive_repeat(input: &[i32], passes: usize) -> Option<Repeat<i32>> {
+        let mut seen = vec![0];
+        let mut acc = 0;
+
+        for pass in 0..passes {
+            for (index, x) in input.iter().enumerate() {
+                acc += x;
+                if let Some(first_seen) = seen.iter().position(|&f| f == acc) {
+                    return Some(Repeat {
+                        frequency: acc,
+                        passes: pass,
+                        index,
+                        first_seen,
+                    });
+                }
+                seen.push(acc);
+            }
+        }
+        None
+    }
+
+    fn naive_part2(input: &[i32], passes: usize) -> Option<i32> {
+        naive_repeat(input, passes).map(|repeat| repeat.frequency)
+    }
+
+    /// frequency changes that bring the frequency back to zero after one pass
+    fn zero_drift() -> impl Strategy<Value = Vec<i32>> {
+        prop::collection::vec(-50..50, 0..30).prop_map(|mut input| {
+            let sum: i32 = input.iter().sum();
+            input.push(-sum);
+            input
+        })
+    }
+
+    #[test]
+    fn test_get_input() {
+        assert_eq!(vec![1, -2, 3, 1], get_input::<i32>("+1\n-2\n+3\n+1\n".as_bytes()).unwrap());
+        match get_input::<i32>("+1\nfoo\n".as_bytes()) {
+            Err(Error::ParseInt { line, text, .. }) => {
+                assert_eq!(2, line);
+                assert_eq!("foo", text);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_get_input_separators() {
+        let expected = vec![1, -2, 3, 1];
+        assert_eq!(expected, get_input::<i32>("+1, -2, +3, +1".as_bytes()).unwrap());
+        assert_eq!(expected, get_input::<i32>("1 -2\n\n  3,+1  \n".as_bytes()).unwrap());
+        assert_eq!(expected, get_input::<i32>("+1,-2,\t+3\r\n+1,\n".as_bytes()).unwrap());
+
+        match get_input::<i32>("+1, -2\n\n+3, --1\n".as_bytes()) {
+            Err(Error::ParseInt { line, text, .. }) => {
+                assert_eq!(3, line);
+                assert_eq!("--1", text);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match get_input::<i32>("+1; -2\n".as_bytes()) {
+            Err(Error::ParseInt { line, text, .. }) => {
+                assert_eq!(1, line);
+                assert_eq!("+1;", text);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
 
     #[test]
     fn test_part1() {
-        assert_eq!(3, part1(&vec![1, -2, 3, 1]));
-        assert_eq!(3, part1(&vec![1, 1, 1]));
-        assert_eq!(0, part1(&vec![1, 1, -2]));
-        assert_eq!(-6, part1(&vec![-1, -2, -3]));
+        assert_eq!(3, part1(&[1, -2, 3, 1]));
+        assert_eq!(3, part1(&[1, 1, 1]));
+        assert_eq!(0, part1(&[1, 1, -2]));
+        assert_eq!(-6, part1(&[-1, -2, -3]));
+    }
+
+    #[test]
+    fn test_overflow() {
+        match part1_checked(&[i32::MAX, 1]) {
+            Err(Error::Overflow(ty)) => assert_eq!("i32", ty),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match part2(&[i32::MAX, 1, -1]) {
+            Err(Error::Overflow(ty)) => assert_eq!("i32", ty),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let max = i64::from(i32::MAX);
+        assert_eq!(max + 1, part1_checked(&[max, 1]).unwrap());
+        assert_eq!(Some(max), part2(&[max, 1, -1]).unwrap());
+        assert_eq!(Some(max), part2_simulate(&[max, 1, -1]).unwrap());
+    }
+
+    #[test]
+    fn test_part1_streaming() {
+        assert_eq!(3, part1_streaming::<i32>("+1, -2\n+3\n+1".as_bytes()).unwrap());
+        assert_eq!(0, part1_streaming::<i32>("".as_bytes()).unwrap());
+
+        match part1_streaming::<i32>("+1\n\nfoo\n".as_bytes()) {
+            Err(Error::ParseInt { line, text, .. }) => {
+                assert_eq!(3, line);
+                assert_eq!("foo", text);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match part1_streaming::<i32>("2147483647\n+1\n".as_bytes()) {
+            Err(Error::Overflow(ty)) => assert_eq!("i32", ty),
+            other => panic!("unexpected result: {:?}", other),
+        }
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(2, part2(&vec![1, -2, 3, 1]));
-        assert_eq!(0, part2(&vec![1, -1]));
-        assert_eq!(10, part2(&vec![3, 3, 4, -2, -4]));
-        assert_eq!(5, part2(&vec![-6, 3, 8, 5, -6]));
-        assert_eq!(14, part2(&vec![7, 7, -2, -7, -4]));
+        assert_eq!(Some(2), part2(&[1, -2, 3, 1]).unwrap());
+        assert_eq!(Some(0), part2(&[1, -1]).unwrap());
+        assert_eq!(Some(10), part2(&[3, 3, 4, -2, -4]).unwrap());
+        assert_eq!(Some(5), part2(&[-6, 3, 8, 5, -6]).unwrap());
+        assert_eq!(Some(14), part2(&[7, 7, -2, -7, -4]).unwrap());
+    }
+
+    #[test]
+    fn test_find_repeat() {
+        let repeat = Repeat {
+            frequency: 2,
+            passes: 1,
+            index: 1,
+            first_seen: 3,
+        };
+        assert_eq!(Some(repeat), find_repeat(&[1, -2, 3, 1]).unwrap());
+
+        let repeat = Repeat {
+            frequency: 0,
+            passes: 0,
+            index: 1,
+            first_seen: 0,
+        };
+        assert_eq!(Some(repeat), find_repeat(&[1, -1]).unwrap());
+    }
+
+    #[test]
+    fn test_part2_simulate() {
+        assert_eq!(Some(2), part2_simulate(&[1, -2, 3, 1]).unwrap());
+        assert_eq!(Some(0), part2_simulate(&[1, -1]).unwrap());
+        assert_eq!(Some(10), part2_simulate(&[3, 3, 4, -2, -4]).unwrap());
+        assert_eq!(Some(5), part2_simulate(&[-6, 3, 8, 5, -6]).unwrap());
+        assert_eq!(Some(14), part2_simulate(&[7, 7, -2, -7, -4]).unwrap());
+        assert_eq!(None, part2_simulate(&[1, 1, 1]).unwrap());
+    }
+
+    #[test]
+    fn test_part2_no_repeat() {
+        assert_eq!(None, part2::<i32>(&[]).unwrap());
+        assert_eq!(None, part2(&[1, 1, 1]).unwrap());
+        assert_eq!(None, part2(&[-3, 1]).unwrap());
+        assert_eq!(Some(0), part2(&[0]).unwrap());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_part1_is_sum(input in prop::collection::vec(-1000..1000, 0..100)) {
+            let sum: i64 = input.iter().map(|&x| i64::from(x)).sum();
+            prop_assert_eq!(sum, i64::from(part1(&input)));
+        }
+
+        #[test]
+        fn prop_part1_checked_detects_overflow(input in prop::collection::vec(any::<i64>(), 0..5)) {
+            // every partial sum has to fit, not only the total
+            let sums = input.iter().scan(0, |acc, &x| {
+                *acc += i128::from(x);
+                Some(*acc)
+            }).collect::<Vec<_>>();
+            let fits = sums.iter().all(|&sum| i64::try_from(sum).is_ok());
+
+            match part1_checked(&input) {
+                Ok(sum) => prop_assert_eq!(sums.last().cloned().unwrap_or(0), i128::from(sum)),
+                Err(_) => prop_assert!(!fits),
+            }
+        }
+
+        #[test]
+        fn prop_part2_matches_naive(input in zero_drift()) {
+            prop_assert_eq!(naive_part2(&input, 2), part2(&input).unwrap());
+            prop_assert_eq!(naive_part2(&input, 2), part2_simulate(&input).unwrap());
+        }
+
+        #[test]
+        fn prop_part2_detects_no_repeat(input in prop::collection::vec(-20..20, 0..10)) {
+            // prefix sums are within -200..200, so any repeat happens within 400 passes
+            prop_assert_eq!(naive_part2(&input, 400), part2(&input).unwrap());
+            prop_assert_eq!(naive_part2(&input, 400), part2_simulate(&input).unwrap());
+        }
+
+        #[test]
+        fn prop_find_repeat_matches_naive(input in prop::collection::vec(-20..20, 0..10)) {
+            prop_assert_eq!(naive_repeat(&input, 400), find_repeat(&input).unwrap());
+        }
+
+        #[test]
+        fn prop_part2_matches_simulation(input in prop::collection::vec(-1000..1000, 0..100)) {
+            prop_assert_eq!(part2_simulate(&input).unwrap(), part2(&input).unwrap());
+        }
+
+        #[test]
+        fn prop_get_input_roundtrip(input in prop::collection::vec(any::<i32>(), 0..100)) {
+            let data = input.iter().map(|x| format!("{:+}\n", x)).collect::<String>();
+            prop_assert_eq!(input, get_input(data.as_bytes()).unwrap());
+        }
+
+        #[test]
+        fn prop_part1_streaming_matches_buffered(input in prop::collection::vec(-1000..1000, 0..100)) {
+            let data = input.iter().map(|x| format!("{:+}\n", x)).collect::<String>();
+            let buffered = part1_checked(&get_input::<i64>(data.as_bytes()).unwrap()).unwrap();
+            prop_assert_eq!(buffered, part1_streaming(data.as_bytes()).unwrap());
+        }
+
+        #[test]
+        fn prop_get_input_any_separator(
+            input in prop::collection::vec((any::<i32>(), any::<bool>(), "[ ,\t\n]{1,3}"), 0..100)
+        ) {
+            let data = input
+                .iter()
+                .map(|(x, sign, sep)| if *sign { format!("{:+}{}", x, sep) } else { format!("{}{}", x, sep) })
+                .collect::<String>();
+            let expected = input.iter().map(|(x, _, _)| *x).collect::<Vec<_>>();
+            prop_assert_eq!(expected, get_input(data.as_bytes()).unwrap());
+        }
     }
 }
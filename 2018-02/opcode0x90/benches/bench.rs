@@ -1,23 +1,70 @@
 #[macro_use]
 extern crate criterion;
-extern crate aoc;
+extern crate aoc_2018_02;
+extern crate aoc_bench;
+extern crate rand;
 
-use aoc::*;
-use criterion::Criterion;
+mod synthetic;
 
-fn bench_part1(c: &mut Criterion) {
-    c.bench_function("part1", |b| {
-        let input = get_input().unwrap();
-        b.iter(|| part1(&input))
-    });
+use aoc_2018_02::{checksum, checksum_hashmap, get_input, part2, part2_pairwise, Day02};
+use aoc_bench::{bench, bench_synthetic, scaled, Scaled};
+use criterion::{Criterion, ParameterizedBenchmark, Throughput};
+
+fn bench_day02(c: &mut Criterion) {
+    bench::<Day02>(c);
+}
+
+fn bench_day02_synthetic(c: &mut Criterion) {
+    bench_synthetic::<Day02>(c, &[1, 10, 100], synthetic::generate);
 }
 
-fn bench_part2(c: &mut Criterion) {
-    c.bench_function("part2", |b| {
-        let input = get_input().unwrap();
-        b.iter(|| part2(&input))
-    });
+/// compare both part2 searches, the masked one up to 100k IDs
+///
+/// the pairwise search is quadratic and already takes seconds per iteration at
+/// 10k IDs, so it stops there: 100k IDs would take 100 times as long, hours over
+/// all the iterations of a benchmark.
+fn bench_part2_masked(c: &mut Criterion) {
+    let pairwise = ParameterizedBenchmark::new(
+        "pairwise",
+        |b, &count| {
+            let input = get_input(synthetic::ids(count).as_bytes()).unwrap();
+            b.iter(|| part2_pairwise(&input).unwrap())
+        },
+        vec![1_000, 10_000],
+    ).sample_size(10);
+
+    let masked = ParameterizedBenchmark::new(
+        "masked",
+        |b, &count| {
+            let input = get_input(synthetic::ids(count).as_bytes()).unwrap();
+            b.iter(|| part2(&input).unwrap())
+        },
+        vec![1_000, 10_000, 100_000],
+    ).sample_size(10);
+
+    c.bench("2018-02/part2", pairwise);
+    c.bench("2018-02/part2", masked);
+}
+
+fn bench_part1_histogram(c: &mut Criterion) {
+    let inputs = scaled::<Day02>(&[1, 10, 100], synthetic::generate);
+
+    let benchmark = ParameterizedBenchmark::new(
+        "hashmap",
+        |b, s: &Scaled<Vec<String>>| b.iter(|| checksum_hashmap(&s.input, &[2, 3])),
+        inputs,
+    ).with_function("histogram", |b, s| b.iter(|| checksum(&s.input, &[2, 3])))
+    .throughput(|s| Throughput::Bytes(s.data.len() as u32))
+    .sample_size(10);
+
+    c.bench("2018-02/part1", benchmark);
 }
 
-criterion_group!(benches, bench_part1, bench_part2);
+criterion_group!(
+    benches,
+    bench_day02,
+    bench_day02_synthetic,
+    bench_part2_masked,
+    bench_part1_histogram
+);
 criterion_main!(benches);
@@ -0,0 +1,35 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// number of box IDs in input.txt
+const IDS: usize = 250;
+
+/// length of each box ID in input.txt
+const ID_LEN: usize = 26;
+
+/// generate `scale` times as many random box IDs as input.txt
+pub fn generate(scale: usize) -> String {
+    ids(IDS * scale)
+}
+
+/// generate `count` random box IDs
+///
+/// only the last two IDs differ by exactly one character, which is the
+/// worst case for the pairwise search in part2
+pub fn ids(count: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(2018);
+    let mut random_id = || {
+        (0..ID_LEN)
+            .map(|_| rng.gen_range(b'a', b'z' + 1) as char)
+            .collect::<String>()
+    };
+
+    let mut ids = (0..count - 1).map(|_| random_id()).collect::<Vec<_>>();
+
+    // plant the correct box IDs at the very end
+    let mut id = ids[ids.len() - 1].clone().into_bytes();
+    id[ID_LEN / 2] = if id[ID_LEN / 2] == b'a' { b'b' } else { b'a' };
+    ids.push(String::from_utf8(id).unwrap());
+
+    ids.join("\n")
+}
@@ -0,0 +1,287 @@
+use std::io::{self, BufRead, Write};
+
+use crate::{measure, Distance};
+
+/// an ID found by a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match<'a> {
+    /// index of the ID in the input
+    pub index: usize,
+    /// the ID itself
+    pub id: &'a str,
+    /// distance to the query
+    pub distance: usize,
+}
+
+#[derive(Debug)]
+struct Node {
+    /// index of the ID in the input
+    index: usize,
+    /// (distance to this node, child node)
+    children: Vec<(usize, usize)>,
+}
+
+impl Node {
+    /// children that may hold an ID at most `d` away from a query `distance` away from this node
+    fn candidates(&self, distance: usize, d: usize) -> impl Iterator<Item = usize> + '_ {
+        let keys = distance.saturating_sub(d)..=distance.saturating_add(d);
+        self.children
+            .iter()
+            .filter(move |(key, _)| keys.contains(key))
+            .map(|&(_, child)| child)
+    }
+}
+
+/// BK-tree over box IDs, for near-match queries without comparing every ID
+///
+/// every child is keyed by its distance to its parent, so by the triangle
+/// inequality only the children keyed within `d` of the query's distance to
+/// the parent can hold an ID at most `d` away from the query.
+#[derive(Debug)]
+pub struct BkTree {
+    distance: Distance,
+    ids: Vec<String>,
+    chars: Vec<Vec<char>>,
+    nodes: Vec<Node>,
+}
+
+impl BkTree {
+    /// index the given IDs, as returned by `get_input`
+    pub fn new(ids: Vec<String>, distance: Distance) -> BkTree {
+        let chars = ids.iter().map(|id| id.chars().collect()).collect();
+        let mut tree = BkTree {
+            distance,
+            ids,
+            chars,
+            nodes: Vec::new(),
+        };
+
+        for index in 0..tree.ids.len() {
+            tree.insert(index);
+        }
+        tree
+    }
+
+    fn insert(&mut self, index: usize) {
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            index,
+            children: Vec::new(),
+        });
+        if node == 0 {
+            return;
+        }
+
+        // walk down from the root until there is no child at that distance
+        let mut parent = 0;
+        loop {
+            let key = self.measure(self.nodes[parent].index, &self.chars[index]);
+            let child = self.nodes[parent]
+                .children
+                .iter()
+                .find(|&&(k, _)| k == key)
+                .map(|&(_, child)| child);
+
+            match child {
+                Some(child) => parent = child,
+                None => {
+                    self.nodes[parent].children.push((key, node));
+                    return;
+                }
+            }
+        }
+    }
+
+    fn measure(&self, index: usize, query: &[char]) -> usize {
+        measure(self.distance, &self.chars[index], query)
+    }
+
+    fn found(&self, index: usize, distance: usize) -> Match<'_> {
+        Match {
+            index,
+            id: &self.ids[index],
+            distance,
+        }
+    }
+
+    /// number of indexed IDs
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// every ID at most `d` away from `query`, closest first
+    pub fn within(&self, query: &str, d: usize) -> Vec<Match<'_>> {
+        let query = query.chars().collect::<Vec<_>>();
+        let mut found = Vec::new();
+        let mut pending = if self.nodes.is_empty() { vec![] } else { vec![0] };
+
+        while let Some(node) = pending.pop() {
+            let node = &self.nodes[node];
+            let distance = self.measure(node.index, &query);
+
+            if distance <= d {
+                found.push(self.found(node.index, distance));
+            }
+            pending.extend(node.candidates(distance, d));
+        }
+
+        found.sort_by_key(|m| (m.distance, m.index));
+        found
+    }
+
+    /// the closest ID to `query`, the first one in the input on ties
+    pub fn nearest(&self, query: &str) -> Option<Match<'_>> {
+        let query = query.chars().collect::<Vec<_>>();
+        let mut best: Option<(usize, usize)> = None;
+        let mut pending = if self.nodes.is_empty() { vec![] } else { vec![0] };
+
+        while let Some(node) = pending.pop() {
+            let node = &self.nodes[node];
+            let distance = self.measure(node.index, &query);
+            let candidate = (distance, node.index);
+
+            best = Some(best.map_or(candidate, |best| best.min(candidate)));
+
+            // anything further than the best so far cannot win, even on ties
+            let d = best.map_or(distance, |(d, _)| d);
+            pending.extend(node.candidates(distance, d));
+        }
+
+        best.map(|(distance, index)| self.found(index, distance))
+    }
+}
+
+const HELP: &str = "\
+commands:
+  nearest <id>        closest ID to <id>
+  within <d> <id>     every ID at most <d> away from <id>
+  help                show this message
+  quit                exit";
+
+/// answer near-match queries read line by line from `input`, until it ends or `quit`
+pub fn repl(tree: &BkTree, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    writeln!(output, "{} IDs indexed, type help for the commands", tree.len())?;
+    write!(output, "> ")?;
+    output.flush()?;
+
+    for line in input.lines() {
+        let line = line?;
+        let words = line.split_whitespace().collect::<Vec<_>>();
+
+        match words[..] {
+            [] => (),
+            ["quit"] | ["exit"] => break,
+            ["help"] => writeln!(output, "{}", HELP)?,
+            ["nearest", query] => match tree.nearest(query) {
+                Some(m) => writeln!(output, "#{} {} (distance {})", m.index, m.id, m.distance)?,
+                None => writeln!(output, "no IDs indexed")?,
+            },
+            ["within", d, query] => match d.parse::<usize>() {
+                Ok(d) => {
+                    let found = tree.within(query, d);
+                    for m in &found {
+                        writeln!(output, "#{} {} (distance {})", m.index, m.id, m.distance)?;
+                    }
+                    writeln!(output, "{} IDs found", found.len())?;
+                }
+                Err(err) => writeln!(output, "invalid distance {:?}: {}", d, err)?,
+            },
+            _ => writeln!(output, "unknown command {:?}, type help for the commands", line)?,
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    writeln!(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn tree(ids: &[&str], distance: Distance) -> BkTree {
+        BkTree::new(ids.iter().map(|&id| String::from(id)).collect(), distance)
+    }
+
+    /// naive reference implementation of within, measures every ID
+    fn naive_within(ids: &[String], distance: Distance, query: &str, d: usize) -> Vec<(usize, usize)> {
+        let query = query.chars().collect::<Vec<_>>();
+        let mut found = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (measure(distance, &id.chars().collect::<Vec<_>>(), &query), i))
+            .filter(|&(dist, _)| dist <= d)
+            .collect::<Vec<_>>();
+        found.sort();
+        found
+    }
+
+    fn distances() -> impl Strategy<Value = Distance> {
+        prop_oneof![Just(Distance::Hamming), Just(Distance::Levenshtein)]
+    }
+
+    #[test]
+    fn test_queries() {
+        let tree = tree(
+            &["abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz"],
+            Distance::Hamming,
+        );
+
+        let found = tree.within("fghij", 1);
+        assert_eq!(vec![1, 4], found.iter().map(|m| m.index).collect::<Vec<_>>());
+        assert_eq!(vec![0, 1], found.iter().map(|m| m.distance).collect::<Vec<_>>());
+
+        let nearest = tree.nearest("fgxij").unwrap();
+        assert_eq!((1, "fghij", 1), (nearest.index, nearest.id, nearest.distance));
+
+        assert_eq!(None, BkTree::new(vec![], Distance::Hamming).nearest("abc"));
+    }
+
+    #[test]
+    fn test_repl() {
+        let tree = tree(&["abcde", "fghij", "fguij"], Distance::Levenshtein);
+        let mut output = Vec::new();
+
+        repl(&tree, "nearest fgij\nwithin 1 fghij\nwithin x y\nfoo\nquit\nnearest abc\n".as_bytes(), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let expected = "\
+3 IDs indexed, type help for the commands
+> #1 fghij (distance 1)
+> #1 fghij (distance 0)
+#2 fguij (distance 1)
+2 IDs found
+> invalid distance \"x\": invalid digit found in string
+> unknown command \"foo\", type help for the commands\n> \n";
+        assert_eq!(expected, output);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_within_matches_naive(
+            ids in prop::collection::vec("[a-c]{0,5}", 0..30),
+            query in "[a-c]{0,5}",
+            d in 0..4usize,
+            distance in distances(),
+        ) {
+            let tree = BkTree::new(ids.clone(), distance);
+            let found = tree.within(&query, d).iter().map(|m| (m.distance, m.index)).collect::<Vec<_>>();
+            prop_assert_eq!(naive_within(&ids, distance, &query, d), found);
+        }
+
+        #[test]
+        fn prop_nearest_matches_naive(
+            ids in prop::collection::vec("[a-c]{0,5}", 1..30),
+            query in "[a-c]{0,5}",
+            distance in distances(),
+        ) {
+            let tree = BkTree::new(ids.clone(), distance);
+            let nearest = tree.nearest(&query).map(|m| (m.distance, m.index));
+            prop_assert_eq!(naive_within(&ids, distance, &query, usize::MAX).first().cloned(), nearest);
+        }
+    }
+}
@@ -1,59 +1,119 @@
+extern crate aoc_common;
 extern crate itertools;
 
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+mod index;
 
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Read};
+use std::iter;
+
+use aoc_common::{Error, Solution};
 use itertools::Itertools;
 
-pub fn part1(input: &Vec<String>) -> u32 {
-    fn frequency_map(s: &str) -> HashMap<char, u32> {
-        // compute frequency count for each characters in given string
-        let mut dict = HashMap::new();
+pub use crate::index::{repl, BkTree, Match};
+
+/// compute frequency count for each characters in given string
+fn frequency_map(s: &str) -> HashMap<char, u32> {
+    let mut dict = HashMap::new();
+
+    for c in s.chars() {
+        // insert char into dict if not exist, otherwise increment count
+        let i = dict.entry(c).or_insert(0);
+        *i += 1
+    }
+    dict
+}
+
+/// letter counts of an ID
+enum Histogram {
+    /// lowercase ASCII IDs shorter than 256 letters, counted without allocating
+    Ascii([u8; 26]),
+    /// any other ID
+    Unicode(HashMap<char, u32>),
+}
+
+impl Histogram {
+    fn new(id: &str) -> Histogram {
+        // no letter can be counted more than 255 times in the fast path
+        if id.len() > usize::from(u8::MAX) || !id.bytes().all(|b| b.is_ascii_lowercase()) {
+            return Histogram::Unicode(frequency_map(id));
+        }
+
+        let mut counts = [0u8; 26];
+        for b in id.bytes() {
+            counts[usize::from(b - b'a')] += 1;
+        }
+        Histogram::Ascii(counts)
+    }
 
-        for c in s.chars() {
-            // insert char into dict if not exist, otherwise increment count
-            let i = dict.entry(c).or_insert(0);
-            *i += 1
+    /// check if some letter appears exactly `n` times
+    fn contains(&self, n: u32) -> bool {
+        match self {
+            // letters missing from the ID are not counted, even for n = 0
+            Histogram::Ascii(counts) => n > 0 && counts.iter().any(|&count| u32::from(count) == n),
+            Histogram::Unicode(counts) => counts.values().any(|&count| count == n),
         }
-        dict
     }
+}
 
-    let mut two = 0;
-    let mut three = 0;
+/// number of IDs having some letter exactly `n` times, for every requested `n`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    /// number of IDs keyed by multiplicity
+    pub counts: BTreeMap<u32, u32>,
+    /// product of all the counts
+    pub product: u64,
+}
 
-    for line in input.into_iter() {
-        // compute char frequency map
-        let fm = frequency_map(line);
+impl Checksum {
+    /// count the IDs having some letter `n` times, using given histogram
+    fn new(input: &[String], multiplicities: &[u32], histogram: fn(&str) -> Histogram) -> Checksum {
+        let mut counts: BTreeMap<u32, u32> = multiplicities.iter().map(|&n| (n, 0)).collect();
 
-        // check if frequency map contains exactly two/three chars
-        let mut x = 0;
-        let mut y = 0;
+        for line in input.iter() {
+            // each ID is counted at most once per multiplicity
+            let histogram = histogram(line);
 
-        for v in fm.values() {
-            match *v {
-                2 => x = 1,
-                3 => y = 1,
-                _ => (),
-            }
-            // early termination if both condition is already met
-            if x > 0 && y > 0 {
-                break;
+            for (n, count) in counts.iter_mut() {
+                if histogram.contains(*n) {
+                    *count += 1;
+                }
             }
         }
 
-        two += x;
-        three += y;
+        let product = counts.values().map(|&count| u64::from(count)).product();
+        Checksum { counts, product }
     }
+}
 
-    two * three
+/// compute the checksum over any set of multiplicities, part1 being {2, 3}
+///
+/// letters are counted as `char`s rather than bytes, so non-ASCII IDs are fine.
+/// lowercase ASCII IDs take an allocation-free fast path.
+pub fn checksum(input: &[String], multiplicities: &[u32]) -> Checksum {
+    Checksum::new(input, multiplicities, Histogram::new)
 }
 
-pub fn part2(input: &Vec<String>) -> String {
-    input
-        .into_iter()
+/// compute the checksum like `checksum`, always counting letters in a HashMap
+pub fn checksum_hashmap(input: &[String], multiplicities: &[u32]) -> Checksum {
+    Checksum::new(input, multiplicities, |id| Histogram::Unicode(frequency_map(id)))
+}
+
+pub fn part1(input: &[String]) -> u64 {
+    checksum(input, &[2, 3]).product
+}
+
+/// find the two IDs differing by one character by comparing every pair, in O(n² L)
+pub fn part2_pairwise(input: &[String]) -> Result<String, Error> {
+    // count the letters of every ID once, rather than once per pair
+    let lens = input.iter().map(|id| id.chars().count()).collect::<Vec<_>>();
+
+    (0..input.len())
         .tuple_combinations()
-        .find_map(|(a, b)| {
+        .filter(|&(i, j)| lens[i] == lens[j])
+        .find_map(|(i, j)| {
+            let (a, b) = (&input[i], &input[j]);
+
             // find both common and distinct chars
             let mut same = String::with_capacity(a.len());
             let mut diff = 0;
@@ -75,20 +135,329 @@ pub fn part2(input: &Vec<String>) -> String {
                 1 => Some(same),
                 _ => None,
             }
-        }).expect("there is no candidate with distance of one!")
+        }).ok_or(NO_CANDIDATE)
+}
+
+/// find the two IDs differing by one character by masking each position in turn
+///
+/// two IDs differing only at position `p` are equal once `p` is masked out, so
+/// they collide in a hash map keyed by everything but `p`. every colliding pair
+/// is considered, so the result is the same as the pairwise search.
+///
+/// every ID is masked at each of its L positions, and hashing a masked key reads
+/// the rest of the ID, so this takes O(n L²) without ever comparing two IDs.
+pub fn part2(input: &[String]) -> Result<String, Error> {
+    // byte offset of every char boundary, found once rather than once per position
+    let bounds = input
+        .iter()
+        .map(|id| id.char_indices().map(|(i, _)| i).chain(iter::once(id.len())).collect())
+        .collect::<Vec<Vec<_>>>();
+    let len = bounds.iter().map(|b| b.len() - 1).max().unwrap_or(0);
+
+    // earliest pair as (first index, second index, masked position)
+    let mut best: Option<(usize, usize, usize)> = None;
+    let mut masked = HashMap::with_capacity(input.len());
+
+    for p in 0..len {
+        masked.clear();
+
+        for (j, id) in input.iter().enumerate() {
+            let (start, end) = match bounds[j].get(p..=p + 1) {
+                Some(&[start, end]) => (start, end),
+                _ => continue,
+            };
+            let key = (&id[..start], &id[end..]);
+            let c = &id[start..end];
+
+            // only the first ID of each key is kept, any later one with a different
+            // character at `p` pairs with it before pairing with anything else
+            match masked.get(&key) {
+                Some(&(i, other)) if other != c => {
+                    let pair = (i, j, p);
+                    best = Some(best.map_or(pair, |best| best.min(pair)));
+                }
+                Some(_) => (),
+                None => {
+                    masked.insert(key, (j, c));
+                }
+            }
+        }
+    }
+
+    let (i, _, p) = best.ok_or(NO_CANDIDATE)?;
+    Ok(input[i]
+        .chars()
+        .enumerate()
+        .filter(|&(k, _)| k != p)
+        .map(|(_, c)| c)
+        .collect())
+}
+
+/// how far apart two IDs are
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distance {
+    /// number of differing positions, every letter past the end of the shorter ID differing
+    Hamming,
+    /// number of single character insertions, deletions or substitutions
+    Levenshtein,
+}
+
+/// two IDs within some distance of each other
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pair {
+    /// index of the first ID in the input
+    pub first: usize,
+    /// index of the second ID in the input, always after the first one
+    pub second: usize,
+    /// distance between both IDs
+    pub distance: usize,
+    /// positions of the first ID that are substituted or deleted
+    pub positions: Vec<usize>,
+    /// letters both IDs have in common, in order
+    pub common: String,
 }
 
-pub fn get_input() -> Result<Vec<String>, Box<std::error::Error>> {
+/// distance, positions and common letters, as found in `Pair`
+type Alignment = (usize, Vec<usize>, String);
+
+/// differing positions and common letters of any two IDs, if at most `k` apart
+fn hamming(a: &[char], b: &[char], k: usize) -> Option<Alignment> {
+    // every letter past the end of the shorter ID differs
+    let extra = a.len().max(b.len()) - a.len().min(b.len());
+    if extra > k {
+        return None;
+    }
+
+    let mut positions = Vec::new();
+    let mut common = String::with_capacity(a.len());
+
+    for (p, (x, y)) in a.iter().zip(b).enumerate() {
+        if x == y {
+            common.push(*x);
+        } else {
+            positions.push(p);
+
+            // early skipping if too far apart
+            if positions.len() + extra > k {
+                return None;
+            }
+        }
+    }
+    let distance = positions.len() + extra;
+    positions.extend(b.len()..a.len());
+    Some((distance, positions, common))
+}
+
+/// edited positions and common letters of any two IDs, if at most `k` edits apart
+fn levenshtein(a: &[char], b: &[char], k: usize) -> Option<Alignment> {
+    // the length difference alone needs that many insertions or deletions
+    if a.len().max(b.len()) - a.len().min(b.len()) > k {
+        return None;
+    }
+
+    // edits[i][j] is the distance between the first i letters of a and j letters of b
+    let mut edits = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for i in 0..=a.len() {
+        for j in 0..=b.len() {
+            edits[i][j] = match (i, j) {
+                (0, _) => j,
+                (_, 0) => i,
+                _ => {
+                    let substitute = edits[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+                    substitute.min(edits[i - 1][j] + 1).min(edits[i][j - 1] + 1)
+                }
+            };
+        }
+    }
+    let distance = edits[a.len()][b.len()];
+    if distance > k {
+        return None;
+    }
+
+    // walk back from the end to recover one of the cheapest alignments
+    let (mut i, mut j) = (a.len(), b.len());
+    let mut positions = Vec::new();
+    let mut common = Vec::new();
+
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && a[i - 1] == b[j - 1] && edits[i][j] == edits[i - 1][j - 1] {
+            common.push(a[i - 1]);
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && j > 0 && edits[i][j] == edits[i - 1][j - 1] + 1 {
+            positions.push(i - 1);
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && edits[i][j] == edits[i - 1][j] + 1 {
+            positions.push(i - 1);
+            i -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+    positions.reverse();
+    Some((distance, positions, common.into_iter().rev().collect()))
+}
+
+/// alignment of any two IDs, if at most `k` apart
+fn align(distance: Distance, a: &[char], b: &[char], k: usize) -> Option<Alignment> {
+    match distance {
+        Distance::Hamming => hamming(a, b, k),
+        Distance::Levenshtein => levenshtein(a, b, k),
+    }
+}
+
+/// distance between any two IDs
+pub(crate) fn measure(distance: Distance, a: &[char], b: &[char]) -> usize {
+    align(distance, a, b, usize::MAX).map_or(usize::MAX, |(d, _, _)| d)
+}
+
+/// find every pair of IDs at most `k` apart
+///
+/// the Hamming distance only compares letters at the same position, use the
+/// Levenshtein distance to match ragged IDs with shifted letters.
+pub fn pairs_within(input: &[String], k: usize, distance: Distance) -> Result<Vec<Pair>, Error> {
+    let ids = input
+        .iter()
+        .map(|id| id.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut pairs = Vec::new();
+
+    for (first, second) in (0..ids.len()).tuple_combinations() {
+        if let Some((distance, positions, common)) = align(distance, &ids[first], &ids[second], k) {
+            pairs.push(Pair {
+                first,
+                second,
+                distance,
+                positions,
+                common,
+            });
+        }
+    }
+
+    if pairs.is_empty() {
+        return Err(Error::NoSolution("no two IDs are within the given distance"));
+    }
+    Ok(pairs)
+}
+
+/// read the box IDs as (line number, ID), skipping blank lines
+fn read_ids(f: impl Read) -> Result<Vec<(usize, String)>, Error> {
+    let mut ids = Vec::new();
+
+    for (n, line) in BufReader::new(f).lines().enumerate() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            ids.push((n + 1, String::from(line.trim())));
+        }
+    }
+    Ok(ids)
+}
+
+/// read box IDs of any length
+pub fn get_input_ragged(f: impl Read) -> Result<Vec<String>, Error> {
+    let ids = read_ids(f)?;
+
+    Ok(ids.into_iter().map(|(_, id)| id).collect())
+}
+
+pub fn get_input(f: impl Read) -> Result<Vec<String>, Error> {
     // read data from input.txt
-    let f = File::open("input.txt").expect("input.txt not found!");
-    let input = BufReader::new(f).lines().collect::<Result<Vec<_>, _>>()?;
+    let ids = read_ids(f)?;
+
+    // every ID must have as many letters as the first one
+    let len = ids.first().map(|(_, id)| id.chars().count());
+    let lines = ids
+        .iter()
+        .filter(|(_, id)| Some(id.chars().count()) != len)
+        .map(|&(n, _)| n)
+        .collect::<Vec<_>>();
+
+    if !lines.is_empty() {
+        let reason = "ID length differs from the first ID";
+        return Err(Error::Inconsistent { lines, reason });
+    }
+    Ok(ids.into_iter().map(|(_, id)| id).collect())
+}
+
+const NO_CANDIDATE: Error = Error::NoSolution("no two IDs differ by exactly one character");
 
-    Ok(input)
+pub struct Day02;
+
+impl Solution for Day02 {
+    const YEAR: u32 = 2018;
+    const DAY: u32 = 2;
+
+    type Input = Vec<String>;
+    type Part1 = u64;
+    type Part2 = String;
+
+    fn parse(f: impl Read) -> Result<Vec<String>, Error> {
+        get_input(f)
+    }
+
+    fn part1(input: &Vec<String>) -> Result<u64, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Vec<String>) -> Result<String, Error> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    /// naive reference implementation of part1, counts every letter from scratch
+    fn naive_part1(input: &[String]) -> u64 {
+        let two = input.iter().filter(|id| has(id, 2)).count() as u64;
+        let three = input.iter().filter(|id| has(id, 3)).count() as u64;
+        two * three
+    }
+
+    /// check if some letter appears exactly `n` times in given ID
+    fn has(id: &str, n: u32) -> bool {
+        id.chars().any(|c| id.matches(c).count() == n as usize)
+    }
+
+    /// naive reference implementation of part2, compares every pair in full
+    fn naive_part2(input: &[String]) -> Option<String> {
+        for i in 0..input.len() {
+            for j in i + 1..input.len() {
+                let (a, b) = (&input[i], &input[j]);
+                let diff = a.chars().zip(b.chars()).filter(|(x, y)| x != y).count();
+
+                if diff == 1 {
+                    let same = a.chars().zip(b.chars()).filter(|(x, y)| x == y);
+                    return Some(same.map(|(x, _)| x).collect());
+                }
+            }
+        }
+        None
+    }
+
+    /// random box IDs, plus a copy of one of them with one letter changed
+    fn box_ids() -> impl Strategy<Value = Vec<String>> {
+        (prop::collection::vec("[a-e]{6}", 1..20), any::<prop::sample::Index>(), 0..6usize)
+            .prop_map(|(mut input, i, pos)| {
+                let mut id = input[i.index(input.len())].clone().into_bytes();
+                // z never appears in the random IDs
+                id[pos] = b'z';
+                input.push(String::from_utf8(id).unwrap());
+                input
+            })
+    }
+
+    #[test]
+    fn test_get_input() {
+        assert_eq!(
+            vec!["abcde", "fghij"],
+            get_input("abcde\nfghij\n".as_bytes()).unwrap()
+        );
+    }
 
     #[test]
     fn test_part1() {
@@ -97,22 +466,249 @@ mod tests {
             part1(
                 &vec!["abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab"]
                     .into_iter()
-                    .map(|s| String::from(s))
-                    .collect()
+                    .map(String::from)
+                    .collect::<Vec<_>>()
             )
         )
     }
 
+    #[test]
+    fn test_checksum() {
+        let mut input = vec!["abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        let expected = Checksum {
+            counts: vec![(2, 4), (3, 3)].into_iter().collect(),
+            product: 12,
+        };
+        assert_eq!(expected, checksum(&input, &[3, 2]));
+
+        input.push(String::from("aaaabb"));
+        let expected = Checksum {
+            counts: vec![(2, 5), (3, 3), (4, 1)].into_iter().collect(),
+            product: 15,
+        };
+        assert_eq!(expected, checksum(&input, &[2, 3, 4]));
+    }
+
+    #[test]
+    fn test_checksum_fallback() {
+        // too long for the fast path, and non-ASCII
+        let input = vec!["a".repeat(300), String::from("ééb"), String::from("AAb")];
+
+        let expected = Checksum {
+            counts: vec![(2, 2), (300, 1)].into_iter().collect(),
+            product: 2,
+        };
+        assert_eq!(expected, checksum(&input, &[2, 300]));
+        assert_eq!(expected, checksum_hashmap(&input, &[2, 300]));
+    }
+
     #[test]
     fn test_part2() {
-        assert_eq!(
-            "fgij",
-            part2(
-                &vec!["abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz"]
-                    .into_iter()
-                    .map(|s| String::from(s))
-                    .collect()
-            )
-        )
+        let input = vec!["abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!("fgij", part2(&input).unwrap());
+        assert_eq!("fgij", part2_pairwise(&input).unwrap());
+
+        // duplicates differ nowhere, so they are not a candidate
+        let input = vec!["abcde", "abcde", "abxde"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!("abde", part2(&input).unwrap());
+        assert_eq!("abde", part2_pairwise(&input).unwrap());
+
+        // masked letters may take more than one byte
+        let input = vec!["xéyz", "aüé", "aéé"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!("aé", part2(&input).unwrap());
+        assert_eq!("aé", part2_pairwise(&input).unwrap());
+    }
+
+    #[test]
+    fn test_part2_no_candidate() {
+        let input = vec!["abcde", "abcde", "fghij"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert!(part2(&input).is_err());
+        assert!(part2_pairwise(&input).is_err());
+        assert!(part2(&[]).is_err());
+    }
+
+    #[test]
+    fn test_pairs_within() {
+        let input = vec!["abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        let pairs = pairs_within(&input, 2, Distance::Hamming).unwrap();
+        let expected = vec![
+            Pair {
+                first: 0,
+                second: 5,
+                distance: 2,
+                positions: vec![1, 3],
+                common: String::from("ace"),
+            },
+            Pair {
+                first: 1,
+                second: 4,
+                distance: 1,
+                positions: vec![2],
+                common: String::from("fgij"),
+            },
+        ];
+        assert_eq!(expected, pairs);
+
+        match pairs_within(&input, 0, Distance::Hamming) {
+            Err(Error::NoSolution(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pairs_within_levenshtein() {
+        let input = vec!["kitten", "sitting", "kittens"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        let pairs = pairs_within(&input[..2], 3, Distance::Levenshtein).unwrap();
+        let expected = vec![
+            Pair {
+                first: 0,
+                second: 1,
+                distance: 3,
+                positions: vec![0, 4],
+                common: String::from("ittn"),
+            },
+        ];
+        assert_eq!(expected, pairs);
+
+        // the missing letter of "sitting" is one more Hamming difference
+        assert_eq!(expected, pairs_within(&input[..2], 3, Distance::Hamming).unwrap());
+        assert!(pairs_within(&input[..2], 2, Distance::Hamming).is_err());
+
+        let pairs = pairs_within(&input, 1, Distance::Levenshtein).unwrap();
+        let expected = vec![Pair {
+            first: 0,
+            second: 2,
+            distance: 1,
+            positions: vec![],
+            common: String::from("kitten"),
+        }];
+        assert_eq!(expected, pairs);
+    }
+
+    #[test]
+    fn test_measure() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert_eq!(3, measure(Distance::Levenshtein, &chars("kitten"), &chars("sitting")));
+        assert_eq!(3, measure(Distance::Hamming, &chars("kitten"), &chars("sitting")));
+        assert_eq!(1, measure(Distance::Hamming, &chars("fghij"), &chars("fguij")));
+        assert_eq!(2, measure(Distance::Hamming, &chars("abcde"), &chars("abc")));
+        assert_eq!(0, measure(Distance::Levenshtein, &chars(""), &chars("")));
+    }
+
+    #[test]
+    fn test_get_input_unequal_length() {
+        let data = "abcde\nfghij\nklm\n\nnopqrs\nnopqr\n";
+        match get_input(data.as_bytes()) {
+            Err(Error::Inconsistent { lines, .. }) => assert_eq!(vec![3, 5], lines),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(5, get_input_ragged(data.as_bytes()).unwrap().len());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_part1_matches_naive(input in prop::collection::vec("[a-e]{0,8}", 0..20)) {
+            prop_assert_eq!(naive_part1(&input), part1(&input));
+        }
+
+        #[test]
+        fn prop_checksum_matches_naive(
+            input in prop::collection::vec("[a-c]{0,8}", 0..20),
+            multiplicities in prop::collection::btree_set(1..6u32, 0..4),
+        ) {
+            let result = checksum(&input, &multiplicities.iter().cloned().collect::<Vec<_>>());
+            let mut product = 1;
+
+            for n in multiplicities {
+                let count = input.iter().filter(|id| has(id, n)).count() as u32;
+                prop_assert_eq!(Some(&count), result.counts.get(&n));
+                product *= u64::from(count);
+            }
+            prop_assert_eq!(product, result.product);
+        }
+
+        #[test]
+        fn prop_part2_matches_naive(input in box_ids()) {
+            prop_assert_eq!(naive_part2(&input).unwrap(), part2(&input).unwrap());
+            prop_assert_eq!(naive_part2(&input).unwrap(), part2_pairwise(&input).unwrap());
+        }
+
+        #[test]
+        fn prop_checksum_matches_hashmap(
+            input in prop::collection::vec("[a-cAé]{0,8}", 0..20),
+            multiplicities in prop::collection::vec(0..6u32, 0..4),
+        ) {
+            prop_assert_eq!(checksum_hashmap(&input, &multiplicities), checksum(&input, &multiplicities));
+        }
+
+        #[test]
+        fn prop_levenshtein_within_hamming(input in prop::collection::vec("[a-c]{0,5}", 2..10)) {
+            let hamming = pairs_within(&input, 5, Distance::Hamming).unwrap_or_default();
+            let levenshtein = pairs_within(&input, 5, Distance::Levenshtein).unwrap();
+
+            for pair in levenshtein.iter().chain(&hamming) {
+                // whatever is not edited in the first ID is kept in common
+                let len = input[pair.first].chars().count();
+                prop_assert_eq!(len - pair.positions.len(), pair.common.chars().count());
+            }
+            for pair in hamming {
+                let found = levenshtein.iter().find(|p| (p.first, p.second) == (pair.first, pair.second));
+                prop_assert!(found.is_some_and(|p| p.distance <= pair.distance));
+            }
+        }
+
+        #[test]
+        fn prop_pairs_within_matches_naive(
+            input in prop::collection::vec("[a-c]{4}", 0..15),
+            k in 0..5usize,
+        ) {
+            let mut expected = Vec::new();
+
+            for i in 0..input.len() {
+                for j in i + 1..input.len() {
+                    let diff = input[i].chars().zip(input[j].chars()).filter(|(x, y)| x != y).count();
+                    if diff <= k {
+                        expected.push((i, j));
+                    }
+                }
+            }
+
+            match pairs_within(&input, k, Distance::Hamming) {
+                Ok(pairs) => {
+                    for pair in &pairs {
+                        prop_assert!(pair.distance <= k);
+                        prop_assert_eq!(pair.distance, pair.positions.len());
+                        prop_assert_eq!(4 - pair.positions.len(), pair.common.len());
+                    }
+                    let pairs = pairs.iter().map(|pair| (pair.first, pair.second)).collect::<Vec<_>>();
+                    prop_assert_eq!(expected, pairs);
+                }
+                Err(_) => prop_assert!(expected.is_empty()),
+            }
+        }
     }
 }
@@ -1,27 +1,40 @@
 #[macro_use]
 extern crate criterion;
-extern crate aoc;
+extern crate aoc_2018_03;
+extern crate aoc_bench;
+extern crate rand;
 
-use std::fs::File;
+mod synthetic;
 
-use aoc::*;
-use criterion::Criterion;
+use aoc_2018_03::{part1_with, part2_with, Day03, Method};
+use aoc_bench::{bench, bench_synthetic, scaled, Scaled};
+use criterion::{Criterion, ParameterizedBenchmark, Throughput};
 
-fn bench_part1(c: &mut Criterion) {
-    c.bench_function("part1", |b| {
-        let f = File::open("input.txt").expect("input.txt not found!");
-        let input = get_input(f).unwrap();
-        b.iter(|| part1(&input))
-    });
+fn bench_day03(c: &mut Criterion) {
+    bench::<Day03>(c);
 }
 
-fn bench_part2(c: &mut Criterion) {
-    c.bench_function("part2", |b| {
-        let f = File::open("input.txt").expect("input.txt not found!");
-        let input = get_input(f).unwrap();
-        b.iter(|| part2(&input))
-    });
+fn bench_day03_synthetic(c: &mut Criterion) {
+    bench_synthetic::<Day03>(c, &[1, 10, 100], synthetic::generate);
 }
 
-criterion_group!(benches, bench_part1, bench_part2);
+fn bench_method(c: &mut Criterion) {
+    let inputs = scaled::<Day03>(&[1, 10, 100], synthetic::generate);
+
+    let benchmark = ParameterizedBenchmark::new(
+        "part1/grid",
+        |b, s: &Scaled<Vec<_>>| b.iter(|| part1_with(&s.input, Method::Grid)),
+        inputs,
+    ).with_function("part1/sweep", |b, s| {
+        b.iter(|| part1_with(&s.input, Method::SweepLine))
+    }).with_function("part2/grid", |b, s| b.iter(|| part2_with(&s.input, Method::Grid).unwrap()))
+    .with_function("part2/sweep", |b, s| {
+        b.iter(|| part2_with(&s.input, Method::SweepLine).unwrap())
+    }).throughput(|s| Throughput::Bytes(s.data.len() as u32))
+    .sample_size(10);
+
+    c.bench("2018-03/method", benchmark);
+}
+
+criterion_group!(benches, bench_day03, bench_day03_synthetic, bench_method);
 criterion_main!(benches);
@@ -0,0 +1,41 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// number of claims in input.txt
+const CLAIMS: usize = 1357;
+
+/// width and height of the fabric in input.txt
+const FABRIC: f64 = 1000.0;
+
+/// generate `scale` times as many claims as input.txt
+///
+/// the fabric grows along with the claims to keep the same density. claims
+/// come in overlapping pairs, except the last one which is placed alone in
+/// the top left corner, so there is exactly one non-overlapping claim
+pub fn generate(scale: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(2018);
+    let side = (FABRIC * (scale as f64).sqrt()) as usize;
+    let mut data = String::new();
+    let mut id = 1;
+
+    while id < CLAIMS * scale {
+        let x = rng.gen_range(40, side - 30);
+        let y = rng.gen_range(0, side - 30);
+        let width = rng.gen_range(10, 29);
+        let height = rng.gen_range(10, 29);
+
+        // the claim and its overlapping twin
+        data.push_str(&format!("#{} @ {},{}: {}x{}\n", id, x, y, width, height));
+        data.push_str(&format!(
+            "#{} @ {},{}: {}x{}\n",
+            id + 1,
+            x + 1,
+            y + 1,
+            width,
+            height
+        ));
+        id += 2;
+    }
+    data.push_str(&format!("#{} @ 0,0: 30x30\n", id));
+    data
+}
@@ -0,0 +1,250 @@
+use std::cmp::Reverse;
+use std::fmt::Write;
+
+use crate::{sweep_overlaps, Claim};
+
+/// two overlapping claims
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlap {
+    /// ID of the claim listed first in the input
+    pub first: usize,
+    /// ID of the other claim
+    pub second: usize,
+    /// area of the intersection, in square inches
+    pub area: usize,
+}
+
+/// the claim overlapping the most other claims
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contended {
+    /// ID of the claim
+    pub id: usize,
+    /// number of claims it overlaps
+    pub overlaps: usize,
+    /// area of all its intersections, summed over every overlap
+    pub area: usize,
+}
+
+/// graph of claims, with an edge between every two overlapping claims
+#[derive(Debug)]
+pub struct OverlapGraph {
+    /// claim IDs in input order
+    ids: Vec<usize>,
+    /// (first, second) indices of every edge, along with the overlap
+    edges: Vec<(usize, usize, Overlap)>,
+}
+
+impl Claim {
+    /// area shared by both claims, 0 if they do not overlap
+    fn intersection(&self, other: &Claim) -> usize {
+        let width = self.right().min(other.right()).saturating_sub(self.x.max(other.x));
+        let height = self.bottom().min(other.bottom()).saturating_sub(self.y.max(other.y));
+        width * height
+    }
+}
+
+impl OverlapGraph {
+    pub fn new(input: &[Claim]) -> OverlapGraph {
+        let mut edges = Vec::new();
+
+        sweep_overlaps(input, |a, b| {
+            let (a, b) = (a.min(b), a.max(b));
+            let area = input[a].intersection(&input[b]);
+
+            // no edge is ever made for claims sharing no area
+            if area > 0 {
+                let (first, second) = (input[a].id, input[b].id);
+                edges.push((a, b, Overlap { first, second, area }));
+            }
+        });
+        edges.sort_by_key(|&(a, b, _)| (a, b));
+
+        OverlapGraph {
+            ids: input.iter().map(|c| c.id).collect(),
+            edges,
+        }
+    }
+
+    /// every two overlapping claims, in input order
+    pub fn overlaps(&self) -> Vec<&Overlap> {
+        self.edges.iter().map(|(_, _, overlap)| overlap).collect()
+    }
+
+    /// claim IDs of every group of claims connected by overlaps, isolated claims included
+    ///
+    /// each group is sorted, and groups are sorted by their lowest ID.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        // union-find over the claim indices
+        let mut parent = (0..self.ids.len()).collect::<Vec<_>>();
+
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        for &(a, b, _) in &self.edges {
+            let (a, b) = (root(&mut parent, a), root(&mut parent, b));
+            parent[a.max(b)] = a.min(b);
+        }
+
+        let mut components = vec![Vec::new(); self.ids.len()];
+        for i in 0..self.ids.len() {
+            let r = root(&mut parent, i);
+            components[r].push(self.ids[i]);
+        }
+
+        let mut components = components
+            .into_iter()
+            .filter(|ids| !ids.is_empty())
+            .map(|mut ids| {
+                ids.sort_unstable();
+                ids
+            }).collect::<Vec<_>>();
+        components.sort();
+        components
+    }
+
+    /// the claim overlapping the most others, the largest area and then the lowest ID breaking ties
+    pub fn most_contended(&self) -> Option<Contended> {
+        let mut overlaps = vec![0; self.ids.len()];
+        let mut area = vec![0; self.ids.len()];
+
+        for (a, b, overlap) in &self.edges {
+            for &i in &[*a, *b] {
+                overlaps[i] += 1;
+                area[i] += overlap.area;
+            }
+        }
+
+        (0..self.ids.len())
+            .filter(|&i| overlaps[i] > 0)
+            .max_by_key(|&i| (overlaps[i], area[i], Reverse(self.ids[i])))
+            .map(|i| Contended {
+                id: self.ids[i],
+                overlaps: overlaps[i],
+                area: area[i],
+            })
+    }
+
+    /// export in the DOT format of graphviz, each edge labelled with its area
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph claims {\n");
+
+        for id in &self.ids {
+            writeln!(dot, "    {};", id).unwrap();
+        }
+        for (_, _, overlap) in &self.edges {
+            writeln!(
+                dot,
+                "    {} -- {} [label={}];",
+                overlap.first, overlap.second, overlap.area
+            ).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_input;
+    use proptest::prelude::*;
+
+    fn graph(data: &str) -> OverlapGraph {
+        OverlapGraph::new(&get_input(data.as_bytes()).unwrap())
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let graph = graph("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n#4 @ 4,4: 2x2\n");
+        let expected = [
+            Overlap {
+                first: 1,
+                second: 2,
+                area: 4,
+            },
+            Overlap {
+                first: 1,
+                second: 4,
+                area: 2,
+            },
+            Overlap {
+                first: 2,
+                second: 4,
+                area: 2,
+            },
+            Overlap {
+                first: 3,
+                second: 4,
+                area: 1,
+            },
+        ];
+        assert_eq!(expected.iter().collect::<Vec<_>>(), graph.overlaps());
+    }
+
+    #[test]
+    fn test_components() {
+        let graph = graph("#5 @ 0,0: 2x2\n#1 @ 9,9: 1x1\n#3 @ 1,1: 2x2\n#2 @ 2,2: 2x2\n#4 @ 20,20: 2x2\n#6 @ 21,21: 1x1\n");
+        assert_eq!(vec![vec![1], vec![2, 3, 5], vec![4, 6]], graph.components());
+
+        let contended = Contended {
+            id: 3,
+            overlaps: 2,
+            area: 2,
+        };
+        assert_eq!(Some(contended), graph.most_contended());
+        assert_eq!(None, OverlapGraph::new(&[]).most_contended());
+    }
+
+    #[test]
+    fn test_empty_claims() {
+        let graph = graph("#1 @ 0,0: 0x5\n#2 @ 3,0: 2x2\n#3 @ 0,0: 5x5\n");
+        assert!(graph.overlaps().iter().all(|o| o.area > 0));
+        assert_eq!(vec![vec![1], vec![2, 3]], graph.components());
+        assert_eq!(Some(2), graph.most_contended().map(|c| c.id));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let graph = graph("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2\n");
+        let expected = "graph claims {\n    1;\n    2;\n    3;\n    1 -- 2 [label=4];\n}\n";
+        assert_eq!(expected, graph.to_dot());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_overlaps_match_naive(claims in prop::collection::vec((0..10usize, 0..10usize, 0..6usize, 0..6usize), 0..20)) {
+            let data = claims
+                .iter()
+                .enumerate()
+                .map(|(i, (x, y, w, h))| format!("#{} @ {},{}: {}x{}\n", i + 1, x, y, w, h))
+                .collect::<String>();
+            let input = get_input(data.as_bytes()).unwrap();
+            let graph = OverlapGraph::new(&input);
+
+            // count the shared square inches of every pair
+            let mut expected = Vec::new();
+            for (i, a) in claims.iter().enumerate() {
+                for (j, b) in claims.iter().enumerate().skip(i + 1) {
+                    let width = (a.0 + a.2).min(b.0 + b.2).saturating_sub(a.0.max(b.0));
+                    let height = (a.1 + a.3).min(b.1 + b.3).saturating_sub(a.1.max(b.1));
+                    if width * height > 0 {
+                        expected.push((i + 1, j + 1, width * height));
+                    }
+                }
+            }
+
+            let overlaps = graph.overlaps().iter().map(|o| (o.first, o.second, o.area)).collect::<Vec<_>>();
+            prop_assert_eq!(expected, overlaps);
+
+            // every claim is in exactly one component
+            let mut ids = graph.components().concat();
+            ids.sort_unstable();
+            prop_assert_eq!((1..=claims.len()).collect::<Vec<_>>(), ids);
+        }
+    }
+}
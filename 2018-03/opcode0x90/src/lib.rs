@@ -1,11 +1,16 @@
+extern crate aoc_common;
 extern crate regex;
 
-use std::collections::{HashMap, HashSet};
-use std::error::Error;
+mod graph;
+
 use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
 
+use aoc_common::{Error, Solution};
 use regex::Regex;
 
+pub use crate::graph::{Contended, Overlap, OverlapGraph};
+
 #[derive(Debug)]
 pub struct Claim {
     id: usize,
@@ -15,13 +20,27 @@ pub struct Claim {
     height: usize,
 }
 
-#[derive(Debug)]
-pub struct Mark {
-    claims: HashSet<usize>,
-    count: usize,
+/// largest bounding box, in square inches, covered by a dense grid
+const GRID_LIMIT: usize = 1 << 24;
+
+/// how the claims are laid over the fabric
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// count the claims of every square inch within the bounding box
+    Grid,
+    /// sweep across the claims' edges, for very large or sparse coordinates
+    SweepLine,
 }
 
-type Fabric = HashMap<(usize, usize), Mark>;
+impl Method {
+    /// use the grid unless the bounding box is too large for it
+    fn choose(input: &[Claim]) -> Method {
+        match Grid::area(input) {
+            Some(area) if area <= GRID_LIMIT => Method::Grid,
+            _ => Method::SweepLine,
+        }
+    }
+}
 
 impl Claim {
     fn right(&self) -> usize {
@@ -30,104 +49,398 @@ impl Claim {
     fn bottom(&self) -> usize {
         self.y + self.height
     }
-    /// mark the claim on fabric
-    fn mark(&self, fabric: &mut Fabric) -> () {
-        for x in self.x..self.right() {
-            for y in self.y..self.bottom() {
-                let coord = (x, y);
+}
 
-                // this is mine now!
-                let mark = fabric.entry(coord).or_insert(Mark {
-                    claims: HashSet::new(),
-                    count: 0,
-                });
+/// number of claims on every square inch of the claims' bounding box
+struct Grid {
+    x: usize,
+    y: usize,
+    width: usize,
+    counts: Vec<u16>,
+}
+
+impl Grid {
+    /// bounding box of the claims as (x, y, width, height)
+    fn bounds(input: &[Claim]) -> (usize, usize, usize, usize) {
+        let x = input.iter().map(|c| c.x).min().unwrap_or(0);
+        let y = input.iter().map(|c| c.y).min().unwrap_or(0);
+        let right = input.iter().map(Claim::right).max().unwrap_or(0);
+        let bottom = input.iter().map(Claim::bottom).max().unwrap_or(0);
+
+        (x, y, right - x, bottom - y)
+    }
+
+    /// area of the bounding box, if it fits in memory at all
+    fn area(input: &[Claim]) -> Option<usize> {
+        let (_, _, width, height) = Grid::bounds(input);
+        width.checked_mul(height)
+    }
 
-                // or is it?
-                mark.claims.insert(self.id);
-                mark.count += 1;
+    fn new(input: &[Claim]) -> Grid {
+        let (x, y, width, height) = Grid::bounds(input);
+        let mut grid = Grid {
+            x,
+            y,
+            width,
+            counts: vec![0; width * height],
+        };
+
+        // process all the claims
+        for claim in input {
+            for row in grid.rows(claim) {
+                for count in &mut grid.counts[row] {
+                    *count = count.saturating_add(1);
+                }
             }
         }
+        grid
+    }
+
+    /// index ranges of every row covered by given claim
+    fn rows(&self, claim: &Claim) -> impl Iterator<Item = Range<usize>> {
+        let (x, y, width) = (self.x, self.y, self.width);
+        let (left, right) = (claim.x - x, claim.right() - x);
+
+        (claim.y - y..claim.bottom() - y).map(move |row| row * width + left..row * width + right)
+    }
+
+    /// check if no other claim covers any of given claim
+    fn isolated(&self, claim: &Claim) -> bool {
+        self.rows(claim)
+            .all(|row| self.counts[row].iter().all(|&count| count == 1))
     }
 }
 
-/// I got lazy and just went with the naive bitmap solution instead
-pub fn part1(input: &Vec<Claim>) -> usize {
-    let mut fabric: Fabric = HashMap::new();
+/// length covered by at least two of given intervals
+fn overlapping_length(intervals: &mut [(usize, isize)]) -> usize {
+    intervals.sort();
+
+    let mut length = 0;
+    let mut depth = 0;
+    let mut last = 0;
 
-    // process all the claims
-    for claim in input {
-        claim.mark(&mut fabric);
+    for &(y, delta) in intervals.iter() {
+        if depth > 1 {
+            length += y - last;
+        }
+        depth += delta;
+        last = y;
     }
+    length
+}
+
+/// edges of every claim as (x, is a left edge, claim), right edges first on ties
+///
+/// empty claims cover nothing, and would otherwise be left active by having
+/// their right edge come first.
+fn edges(input: &[Claim]) -> Vec<(usize, bool, usize)> {
+    let mut edges = input
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.width > 0 && c.height > 0)
+        .flat_map(|(i, c)| [(c.x, true, i), (c.right(), false, i)])
+        .collect::<Vec<_>>();
+    edges.sort();
+    edges
+}
+
+fn part1_sweep(input: &[Claim]) -> usize {
+    let mut active: Vec<usize> = Vec::new();
+    let mut intervals = Vec::new();
+    let mut area = 0;
+    let mut last = 0;
+
+    for (x, left, i) in edges(input) {
+        // measure the strip between the previous edge and this one
+        if x > last {
+            intervals.clear();
+            for &a in &active {
+                intervals.push((input[a].y, 1));
+                intervals.push((input[a].bottom(), -1));
+            }
+            area += (x - last) * overlapping_length(&mut intervals);
+            last = x;
+        }
+
+        if left {
+            active.push(i);
+        } else {
+            active.retain(|&a| a != i);
+        }
+    }
+    area
+}
+
+fn part1_grid(input: &[Claim]) -> usize {
+    let grid = Grid::new(input);
 
     // count areas with more than 1 claims
-    fabric.values().filter(|mark| mark.count > 1).count()
+    grid.counts.iter().filter(|&&count| count > 1).count()
 }
 
-pub fn part2(input: &Vec<Claim>) -> usize {
-    let mut fabric: Fabric = HashMap::new();
+/// count the square inches within two or more claims
+pub fn part1(input: &[Claim]) -> usize {
+    part1_with(input, Method::choose(input))
+}
 
-    // process all the claims
-    for claim in input {
-        claim.mark(&mut fabric);
+pub fn part1_with(input: &[Claim], method: Method) -> usize {
+    match method {
+        Method::Grid => part1_grid(input),
+        Method::SweepLine => part1_sweep(input),
     }
+}
+
+/// call `f` with the indices of every two overlapping claims, by sweeping across their edges
+fn sweep_overlaps(input: &[Claim], mut f: impl FnMut(usize, usize)) {
+    let mut active: Vec<usize> = Vec::new();
 
-    // locate areas that doesn't overlap and extract the claims
-    let mut claims: HashSet<usize> = HashSet::new();
-    let mut overlapped: HashSet<usize> = HashSet::new();
+    for (_, left, i) in edges(input) {
+        if !left {
+            active.retain(|&a| a != i);
+            continue;
+        }
 
-    for mark in fabric.values() {
-        match mark.count {
-            1 => claims.extend(mark.claims.clone()),
-            _ => overlapped.extend(mark.claims.clone()),
+        // every active claim spans this edge, so only the rows have to overlap
+        for &a in &active {
+            if input[a].y < input[i].bottom() && input[i].y < input[a].bottom() {
+                f(a, i);
+            }
         }
+        active.push(i);
     }
+}
+
+/// whether each claim overlaps any other, by sweeping across their edges
+fn overlapped_sweep(input: &[Claim]) -> Vec<bool> {
+    let mut overlapped = vec![false; input.len()];
+
+    sweep_overlaps(input, |a, b| {
+        overlapped[a] = true;
+        overlapped[b] = true;
+    });
+    overlapped
+}
+
+/// whether each claim overlaps any other, by counting the claims of every square inch
+fn overlapped_grid(input: &[Claim]) -> Vec<bool> {
+    let grid = Grid::new(input);
+
+    input.iter().map(|claim| !grid.isolated(claim)).collect()
+}
+
+/// find every claim that overlaps no other claim, sorted by ID
+pub fn isolated(input: &[Claim]) -> Vec<usize> {
+    isolated_with(input, Method::choose(input))
+}
+
+pub fn isolated_with(input: &[Claim], method: Method) -> Vec<usize> {
+    let overlapped = match method {
+        Method::Grid => overlapped_grid(input),
+        Method::SweepLine => overlapped_sweep(input),
+    };
 
     // extract non-overlapping claims
-    let mut id: Vec<&usize> = claims.difference(&overlapped).collect();
+    let mut ids: Vec<usize> = input
+        .iter()
+        .zip(overlapped)
+        .filter(|(_, overlapped)| !overlapped)
+        .map(|(claim, _)| claim.id)
+        .collect();
+    ids.sort_unstable();
+    ids
+}
 
-    // HACK: there should be only one non-overlapping claims
-    assert_eq!(1, id.len());
+/// find the claim that overlaps no other claim, the lowest ID if there are several
+pub fn part2(input: &[Claim]) -> Result<usize, Error> {
+    part2_with(input, Method::choose(input))
+}
 
-    *id.pop()
-        .expect("unable to find any non-overlapping claims!")
+pub fn part2_with(input: &[Claim], method: Method) -> Result<usize, Error> {
+    isolated_with(input, method)
+        .first()
+        .cloned()
+        .ok_or(Error::NoSolution("every claim overlaps another one"))
 }
 
-pub fn get_input(f: impl Read) -> Result<Vec<Claim>, Box<Error>> {
+pub fn get_input(f: impl Read) -> Result<Vec<Claim>, Error> {
     // read data from input.txt
     let input = BufReader::new(f).lines().collect::<Result<Vec<_>, _>>()?;
 
     // parse the input into Claim
-    let re = Regex::new(r"^#(\d+) @ (\d+),(\d+): (\d+)x(\d+)$")?;
+    let re = Regex::new(r"^#(\d+) @ (\d+),(\d+): (\d+)x(\d+)$").expect("invalid regex");
     let claims = input
         .iter()
-        .filter_map(|line| {
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(n, line)| {
             // attempt to parse the input line
-            re.captures(line.as_str())
-        }).map(|parsed| {
-            let try_parse = |n| -> Result<usize, Box<Error>> {
-                Ok(parsed
-                    .get(n)
-                    .ok_or_else(|| "malformed input")?
-                    .as_str()
-                    .parse::<usize>()?)
+            let parsed = re
+                .captures(line.trim())
+                .ok_or_else(|| Error::malformed(n + 1, line, "malformed claim"))?;
+
+            let try_parse = |text: &str| -> Result<usize, Error> {
+                text.parse::<usize>()
+                    .map_err(|err| Error::parse_int(n + 1, text, err))
             };
 
             // extract regexp captured group into Claim
-            Ok(Claim {
-                id: try_parse(1)?,
-                x: try_parse(2)?,
-                y: try_parse(3)?,
-                width: try_parse(4)?,
-                height: try_parse(5)?,
-            })
-        }).collect::<Result<Vec<_>, Box<Error>>>()?;
+            let claim = Claim {
+                id: try_parse(&parsed[1])?,
+                x: try_parse(&parsed[2])?,
+                y: try_parse(&parsed[3])?,
+                width: try_parse(&parsed[4])?,
+                height: try_parse(&parsed[5])?,
+            };
+
+            // both far edges must fit, so `right` and `bottom` never overflow
+            let right = claim.x.checked_add(claim.width);
+            let bottom = claim.y.checked_add(claim.height);
+            if right.is_none() || bottom.is_none() {
+                return Err(Error::malformed(n + 1, line, "claim extends past the largest coordinate"));
+            }
+            Ok(claim)
+        }).collect::<Result<Vec<_>, Error>>()?;
 
     Ok(claims)
 }
 
+pub struct Day03;
+
+impl Solution for Day03 {
+    const YEAR: u32 = 2018;
+    const DAY: u32 = 3;
+
+    type Input = Vec<Claim>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(f: impl Read) -> Result<Vec<Claim>, Error> {
+        get_input(f)
+    }
+
+    fn part1(input: &Vec<Claim>) -> Result<usize, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &Vec<Claim>) -> Result<usize, Error> {
+        part2(input)
+    }
+
+    fn details(input: &Vec<Claim>) -> Result<Option<String>, Error> {
+        let graph = OverlapGraph::new(input);
+
+        // any overlap makes a group of at least two claims, and a most contended one
+        let c = match graph.most_contended() {
+            Some(c) => c,
+            None => return Ok(Some(String::from("no claims overlap"))),
+        };
+        let largest = graph.components().iter().map(Vec::len).max().unwrap_or(0);
+
+        Ok(Some(format!(
+            "{} overlaps, largest group of {} overlapping claims, \
+             claim #{} is the most contended with {} overlaps over {} square inches",
+            graph.overlaps().len(),
+            largest,
+            c.id,
+            c.overlaps,
+            c.area
+        )))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    /// check if two claims share any square inch
+    fn overlaps(a: &Claim, b: &Claim) -> bool {
+        let empty = |c: &Claim| c.width == 0 || c.height == 0;
+        !empty(a)
+            && !empty(b)
+            && a.x < b.right()
+            && b.x < a.right()
+            && a.y < b.bottom()
+            && b.y < a.bottom()
+    }
+
+    /// naive reference implementation of part1, checks every square inch
+    fn naive_part1(input: &[Claim]) -> usize {
+        let right = input.iter().map(Claim::right).max().unwrap_or(0);
+        let bottom = input.iter().map(Claim::bottom).max().unwrap_or(0);
+        let mut count = 0;
+
+        for x in 0..right {
+            for y in 0..bottom {
+                let claimed = input
+                    .iter()
+                    .filter(|c| (c.x..c.right()).contains(&x) && (c.y..c.bottom()).contains(&y))
+                    .count();
+                if claimed > 1 {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// naive reference implementation of part2, checks every pair of claims
+    fn naive_part2(input: &[Claim]) -> Vec<usize> {
+        input
+            .iter()
+            .filter(|a| !input.iter().any(|b| a.id != b.id && overlaps(a, b)))
+            .map(|a| a.id)
+            .collect()
+    }
+
+    /// random claims on a small piece of fabric
+    fn claims() -> impl Strategy<Value = Vec<Claim>> {
+        prop::collection::vec((0..10usize, 0..10usize, 0..6usize, 0..6usize), 0..20).prop_map(
+            |claims| {
+                claims
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (x, y, width, height))| Claim {
+                        id: i + 1,
+                        x,
+                        y,
+                        width,
+                        height,
+                    }).collect()
+            },
+        )
+    }
+
+    /// random claims that each overlaps its twin, plus one isolated claim
+    fn one_isolated() -> impl Strategy<Value = Vec<Claim>> {
+        claims().prop_map(|claims| {
+            let mut input = Vec::new();
+
+            for c in claims {
+                input.push(Claim {
+                    id: input.len() + 1,
+                    width: c.width + 2,
+                    height: c.height + 2,
+                    ..c
+                });
+                input.push(Claim {
+                    id: input.len() + 1,
+                    x: c.x + 1,
+                    y: c.y + 1,
+                    width: c.width + 1,
+                    height: c.height + 1,
+                });
+            }
+            input.push(Claim {
+                id: input.len() + 1,
+                x: 100,
+                y: 100,
+                width: 1,
+                height: 1,
+            });
+            input
+        })
+    }
 
     #[test]
     fn test_part1() {
@@ -148,6 +461,109 @@ mod tests {
 #3 @ 5,5: 2x2
         "#;
         let input = get_input(data.as_bytes()).unwrap();
-        assert_eq!(3, part2(&input));
+        assert_eq!(3, part2(&input).unwrap());
+    }
+
+    #[test]
+    fn test_isolated() {
+        let data = r#"
+#4 @ 9,9: 1x1
+#1 @ 1,3: 4x4
+#2 @ 3,1: 4x4
+#3 @ 5,5: 2x2
+        "#;
+        let input = get_input(data.as_bytes()).unwrap();
+        assert_eq!(vec![3, 4], isolated(&input));
+        assert_eq!(3, part2(&input).unwrap());
+
+        let input = get_input("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n".as_bytes()).unwrap();
+        assert!(isolated(&input).is_empty());
+        match part2(&input) {
+            Err(Error::NoSolution(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sparse() {
+        // far too large a bounding box for the grid
+        let data = r#"
+#1 @ 1000000000,3: 4x4
+#2 @ 1000000002,1: 4x4
+#3 @ 5,5: 2x2
+        "#;
+        let input = get_input(data.as_bytes()).unwrap();
+        assert_eq!(Method::SweepLine, Method::choose(&input));
+        assert_eq!(4, part1(&input));
+        assert_eq!(3, part2(&input).unwrap());
+    }
+
+    #[test]
+    fn test_overflowing_claim() {
+        let data = format!("#1 @ 0,0: 2x2\n#2 @ {},0: 2x2\n", usize::MAX);
+        match get_input(data.as_bytes()) {
+            Err(Error::Malformed { line, text, .. }) => {
+                assert_eq!(2, line);
+                assert_eq!(format!("#2 @ {},0: 2x2", usize::MAX), text);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // the far edge may still land on the largest coordinate itself
+        let data = format!("#1 @ 0,{}: 2x2\n", usize::MAX - 2);
+        assert_eq!(1, part2(&get_input(data.as_bytes()).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_empty_claims() {
+        let data = "#1 @ 0,0: 0x5\n#2 @ 0,0: 5x5\n#3 @ 100000000,0: 1x1\n";
+        let input = get_input(data.as_bytes()).unwrap();
+        assert_eq!(Method::SweepLine, Method::choose(&input));
+
+        for &method in &[Method::Grid, Method::SweepLine] {
+            assert_eq!(0, part1_with(&input, method));
+            assert_eq!(vec![1, 2, 3], isolated_with(&input, method));
+        }
+    }
+
+    #[test]
+    fn test_get_input_malformed() {
+        let data = "#1 @ 1,3: 4x4\n#2 @ 3,1 4x4\n";
+        match get_input(data.as_bytes()) {
+            Err(Error::Malformed { line, text, .. }) => {
+                assert_eq!(2, line);
+                assert_eq!("#2 @ 3,1 4x4", text);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_part1_within_claimed_area(input in claims()) {
+            let area: usize = input.iter().map(|c| c.width * c.height).sum();
+            prop_assert!(part1(&input) <= area);
+        }
+
+        #[test]
+        fn prop_part1_matches_naive(input in claims()) {
+            prop_assert_eq!(naive_part1(&input), part1(&input));
+            prop_assert_eq!(naive_part1(&input), part1_with(&input, Method::Grid));
+            prop_assert_eq!(naive_part1(&input), part1_with(&input, Method::SweepLine));
+        }
+
+        #[test]
+        fn prop_part2_matches_naive(input in one_isolated()) {
+            prop_assert_eq!(naive_part2(&input), vec![part2(&input).unwrap()]);
+            prop_assert_eq!(naive_part2(&input), vec![part2_with(&input, Method::Grid).unwrap()]);
+            prop_assert_eq!(naive_part2(&input), vec![part2_with(&input, Method::SweepLine).unwrap()]);
+        }
+
+        #[test]
+        fn prop_isolated_matches_naive(input in claims()) {
+            prop_assert_eq!(naive_part2(&input), isolated(&input));
+            prop_assert_eq!(naive_part2(&input), isolated_with(&input, Method::Grid));
+            prop_assert_eq!(naive_part2(&input), isolated_with(&input, Method::SweepLine));
+        }
     }
 }
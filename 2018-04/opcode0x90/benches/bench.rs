@@ -1,27 +1,22 @@
 #[macro_use]
 extern crate criterion;
-extern crate aoc;
+extern crate aoc_2018_04;
+extern crate aoc_bench;
+extern crate rand;
 
-use std::fs::File;
+mod synthetic;
 
-use aoc::*;
+use aoc_2018_04::Day04;
+use aoc_bench::{bench, bench_synthetic};
 use criterion::Criterion;
 
-fn bench_part1(c: &mut Criterion) {
-    c.bench_function("part1", |b| {
-        let f = File::open("input.txt").expect("input.txt not found!");
-        let input = get_input(f).unwrap();
-        b.iter(|| part1(&input))
-    });
+fn bench_day04(c: &mut Criterion) {
+    bench::<Day04>(c);
 }
 
-fn bench_part2(c: &mut Criterion) {
-    c.bench_function("part2", |b| {
-        let f = File::open("input.txt").expect("input.txt not found!");
-        let input = get_input(f).unwrap();
-        b.iter(|| part2(&input))
-    });
+fn bench_day04_synthetic(c: &mut Criterion) {
+    bench_synthetic::<Day04>(c, &[1, 10, 100], synthetic::generate);
 }
 
-criterion_group!(benches, bench_part1, bench_part2);
+criterion_group!(benches, bench_day04, bench_day04_synthetic);
 criterion_main!(benches);
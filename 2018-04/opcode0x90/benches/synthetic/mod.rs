@@ -0,0 +1,45 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// number of records in input.txt
+const RECORDS: usize = 1128;
+
+/// number of guards in input.txt
+const GUARDS: usize = 20;
+
+/// generate `scale` times as many shuffled records as input.txt
+pub fn generate(scale: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(2018);
+    let guards = (0..GUARDS)
+        .map(|_| rng.gen_range(1, 4000))
+        .collect::<Vec<usize>>();
+    let mut records = Vec::with_capacity(RECORDS * scale);
+    let mut shift = 0;
+
+    while records.len() < RECORDS * scale {
+        // one shift per day, 28 days a month keeps the dates valid
+        let date = format!(
+            "{}-{:02}-{:02}",
+            1518 + shift / (28 * 12),
+            1 + (shift / 28) % 12,
+            1 + shift % 28
+        );
+        let guard = guards.choose(&mut rng).unwrap();
+        records.push(format!("[{} 00:00] Guard #{} begins shift", date, guard));
+
+        // sleep for a few non-overlapping sessions between 00:01 and 00:59
+        let mut minutes = (1..60).collect::<Vec<_>>();
+        minutes.shuffle(&mut rng);
+        let mut minutes = minutes[..2 * rng.gen_range(1, 4)].to_vec();
+        minutes.sort();
+
+        for session in minutes.chunks(2) {
+            records.push(format!("[{} 00:{:02}] falls asleep", date, session[0]));
+            records.push(format!("[{} 00:{:02}] wakes up", date, session[1]));
+        }
+        shift += 1;
+    }
+    records.shuffle(&mut rng);
+    records.join("\n")
+}
@@ -1,9 +1,10 @@
+extern crate aoc_common;
 extern crate regex;
 
 use std::collections::{BTreeMap, HashMap};
-use std::error::Error;
 use std::io::{BufRead, BufReader, Read};
 
+use aoc_common::{Error, Solution};
 use regex::Regex;
 
 type Minute = usize;
@@ -11,7 +12,6 @@ type GuardID = usize;
 
 #[derive(Debug)]
 enum EventType {
-    Error = 0,
     BeginShift,
     FallAsleep,
     WakesUp,
@@ -31,6 +31,9 @@ pub struct Event {
     timestamp: Timestamp,
     event_type: EventType,
     guard_id: Option<GuardID>,
+    /// 1-based line number and text of the event, for error reporting
+    line: usize,
+    text: String,
 }
 
 /// items in BTreeMap is always ordered, so sorting comes for free!
@@ -40,142 +43,250 @@ type SleepCount = usize;
 type SleepMap = HashMap<Minute, SleepCount>;
 type SleepSession = HashMap<GuardID, SleepMap>;
 
-fn get_sleep_session(input: &Log) -> SleepSession {
+fn get_sleep_session(input: &Log) -> Result<SleepSession, Error> {
     let mut session: SleepSession = HashMap::new();
-    let mut id = 0;
-    let mut sleep_start: Minute = 0;
+    let mut id = None;
+    let mut asleep: Option<(GuardID, Minute)> = None;
 
     for event in input.values() {
+        let malformed = |reason| Error::malformed(event.line, &event.text, reason);
+
         match event.event_type {
-            EventType::BeginShift => id = event.guard_id.expect("guard ID is missing!"),
-            EventType::FallAsleep => sleep_start = event.timestamp.minute,
+            EventType::BeginShift => {
+                id = event.guard_id;
+                asleep = None;
+            }
+            EventType::FallAsleep => {
+                let id = id.ok_or_else(|| malformed("nobody is on duty"))?;
+                asleep = Some((id, event.timestamp.minute));
+            }
             EventType::WakesUp => {
                 let sleep_end = event.timestamp.minute;
 
-                // HACK: sleep should only happen between 00:00 - 00:59
-                assert!(sleep_start < sleep_end);
+                // both are within the midnight hour, so waking up comes at a later minute
+                let (id, sleep_start) = match asleep.take() {
+                    Some((id, start)) if start < sleep_end => (id, start),
+                    Some(_) => return Err(malformed("wakes up before falling asleep")),
+                    None => return Err(malformed("wakes up without falling asleep")),
+                };
 
                 // mark the sleep duration
-                let sleep = session.entry(id).or_insert(SleepMap::new());
+                let sleep = session.entry(id).or_default();
 
                 for m in sleep_start..sleep_end {
                     let count = sleep.entry(m).or_insert(0);
                     *count += 1;
                 }
             }
-            _ => panic!("unknown event type encountered!"),
         }
     }
-    session
+    Ok(session)
 }
 
-pub fn part1(input: &Log) -> usize {
+const NO_SLEEP: Error = Error::NoSolution("nobody has been sleeping on the job");
+
+/// the most slept minute of given sleep map
+fn most_slept(sleep: SleepMap) -> Result<Minute, Error> {
+    sleep
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(m, _)| m)
+        .ok_or(NO_SLEEP)
+}
+
+pub fn part1(input: &Log) -> Result<usize, Error> {
     // parse for sleep session
-    let session = get_sleep_session(input);
+    let session = get_sleep_session(input)?;
 
     // who has been sleeping on the job the most?!
     let (id, sleep) = session
         .into_iter()
         .max_by_key(|(_, sleep)| -> Minute { sleep.values().sum() })
-        .expect("nobody has been sleeping on the job!");
+        .ok_or(NO_SLEEP)?;
 
     // locate the which minute it has been sleeping the most
-    let (m, _) = sleep
-        .into_iter()
-        .max_by_key(|(_, count)| *count)
-        .expect("hmm, it was not found guilty sleeping on the job.");
-
-    id * m
+    Ok(id * most_slept(sleep)?)
 }
 
-pub fn part2(input: &Log) -> usize {
+pub fn part2(input: &Log) -> Result<usize, Error> {
     // parse for sleep session
-    let session = get_sleep_session(input);
+    let session = get_sleep_session(input)?;
 
     // find which guard has been asleep the most on same minute, and which minute it
     let (id, sleep) = session
         .into_iter()
         .max_by_key(|(_, sleep)| -> Minute { *sleep.values().max().unwrap_or(&0) })
-        .expect("nobody has been sleeping on the job!");
+        .ok_or(NO_SLEEP)?;
 
     // which minute is that?
-    let (m, _) = sleep
-        .into_iter()
-        .max_by_key(|(_, count)| *count)
-        .expect("hmm, it was not found guilty sleeping on the job.");
-
-    id * m
+    Ok(id * most_slept(sleep)?)
 }
 
-pub fn get_input(f: impl Read) -> Result<Log, Box<Error>> {
+pub fn get_input(f: impl Read) -> Result<Log, Error> {
     // read data from input.txt
     let input = BufReader::new(f).lines();
 
     // parse input into events
     let mut log: Log = Log::new();
-    let re = Regex::new(r"^\[(\d+)-(\d+)-(\d+)\s+(\d+):(\d+)\]\s+(.+)$")?;
-    let re_guard = Regex::new(r"Guard #(\d+) begins shift")?;
-
-    for line in input {
-        if let Some(parsed) = re.captures(line?.as_str()) {
-            let try_parse = |n| -> Result<usize, Box<Error>> {
-                Ok(parsed
-                    .get(n)
-                    .ok_or_else(|| "malformed input")?
-                    .as_str()
-                    .parse::<usize>()?)
-            };
-
-            let timestamp = Timestamp {
-                year: try_parse(1)?,
-                month: try_parse(2)?,
-                day: try_parse(3)?,
-                hour: try_parse(4)?,
-                minute: try_parse(5)?,
-            };
-            let mut event = Event {
-                timestamp: timestamp.clone(),
-                event_type: EventType::Error,
-                guard_id: None,
-            };
-
-            // what kind of event is this?
-            let msg = parsed
-                .get(6)
-                .ok_or_else(|| "malformed input")?
-                .as_str()
-                .trim();
-
-            if let Some(guard_event) = re_guard.captures(msg) {
-                // a new challenger has arrived!
-                event.event_type = EventType::BeginShift;
-                event.guard_id = Some(
-                    guard_event
-                        .get(1)
-                        .ok_or_else(|| "malformed input: unable to parse guard id!")?
-                        .as_str()
-                        .parse()?,
-                );
-            } else {
-                event.event_type = match msg {
-                    // somebody falls asleep on the job!
-                    "falls asleep" => EventType::FallAsleep,
-                    "wakes up" => EventType::WakesUp,
-                    _ => panic!("malformed input: unrecognized event type"),
-                };
+    let re = Regex::new(r"^\[(\d+)-(\d+)-(\d+)\s+(\d+):(\d+)\]\s+(.+)$").expect("invalid regex");
+    let re_guard = Regex::new(r"^Guard #(\d+) begins shift$").expect("invalid regex");
+
+    for (n, line) in input.enumerate() {
+        let line = line?;
+
+        // skip blank lines
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let parsed = re
+            .captures(line.trim())
+            .ok_or_else(|| Error::malformed(n + 1, &line, "malformed event"))?;
+
+        let try_parse = |text: &str| -> Result<usize, Error> {
+            text.parse::<usize>()
+                .map_err(|err| Error::parse_int(n + 1, text, err))
+        };
+
+        let timestamp = Timestamp {
+            year: try_parse(&parsed[1])?,
+            month: try_parse(&parsed[2])?,
+            day: try_parse(&parsed[3])?,
+            hour: try_parse(&parsed[4])?,
+            minute: try_parse(&parsed[5])?,
+        };
+
+        // what kind of event is this?
+        let msg = parsed[6].trim();
+
+        let (event_type, guard_id) = if let Some(guard_event) = re_guard.captures(msg) {
+            // a new challenger has arrived!
+            (EventType::BeginShift, Some(try_parse(&guard_event[1])?))
+        } else {
+            match msg {
+                // somebody falls asleep on the job!
+                "falls asleep" => (EventType::FallAsleep, None),
+                "wakes up" => (EventType::WakesUp, None),
+                _ => return Err(Error::malformed(n + 1, &line, "unrecognized event type")),
             }
+        };
 
-            // insert event into log
-            log.insert(timestamp, event);
+        // sleep should only happen between 00:00 - 00:59
+        let sleeping = !matches!(event_type, EventType::BeginShift);
+        if sleeping && (timestamp.hour != 0 || timestamp.minute > 59) {
+            return Err(Error::malformed(n + 1, &line, "sleep outside of the midnight hour"));
         }
+
+        // events are ordered by timestamp alone, so no two may share one
+        if log.contains_key(&timestamp) {
+            return Err(Error::malformed(n + 1, &line, "duplicate timestamp"));
+        }
+
+        // insert event into log
+        let event = Event {
+            timestamp: timestamp.clone(),
+            event_type,
+            guard_id,
+            line: n + 1,
+            text: line,
+        };
+        log.insert(timestamp, event);
     }
 
     Ok(log)
 }
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    const YEAR: u32 = 2018;
+    const DAY: u32 = 4;
+
+    type Input = Log;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(f: impl Read) -> Result<Log, Error> {
+        get_input(f)
+    }
+
+    fn part1(input: &Log) -> Result<usize, Error> {
+        part1(input)
+    }
+
+    fn part2(input: &Log) -> Result<usize, Error> {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+    use std::collections::{BTreeSet, HashSet};
+
+    /// minutes each guard has been asleep, counted with a fixed array
+    fn naive_sleep(shifts: &[(GuardID, Vec<Minute>)]) -> HashMap<GuardID, [usize; 60]> {
+        let mut sleep = HashMap::new();
+
+        for (id, minutes) in shifts {
+            let count = sleep.entry(*id).or_insert([0; 60]);
+            for session in minutes.chunks(2) {
+                for c in &mut count[session[0]..session[1]] {
+                    *c += 1;
+                }
+            }
+        }
+        sleep
+    }
+
+    /// every id * minute that is a valid answer for given ranking, ties included
+    fn naive_answers(
+        sleep: &HashMap<GuardID, [usize; 60]>,
+        rank: impl Fn(&[usize; 60]) -> usize,
+    ) -> HashSet<usize> {
+        let best = sleep.values().map(&rank).max().unwrap_or(0);
+        let mut answers = HashSet::new();
+
+        for (id, count) in sleep.iter().filter(|(_, count)| rank(count) == best) {
+            let most = count.iter().max().unwrap();
+            for m in (0..60).filter(|&m| count[m] == *most) {
+                answers.insert(id * m);
+            }
+        }
+        answers
+    }
+
+    /// shifts of a few guards starting at 00:00, each with sleep/wake minutes after that
+    fn shifts() -> impl Strategy<Value = Vec<(GuardID, Vec<Minute>)>> {
+        prop::collection::vec(
+            (1..6usize, prop::collection::btree_set(1..60usize, 0..8)),
+            1..10,
+        ).prop_map(|shifts| {
+            shifts
+                .into_iter()
+                .map(|(id, minutes): (_, BTreeSet<_>)| {
+                    let mut minutes = minutes.into_iter().collect::<Vec<_>>();
+                    minutes.truncate(minutes.len() / 2 * 2);
+                    (id, minutes)
+                }).collect()
+        })
+    }
+
+    fn to_log(shifts: &[(GuardID, Vec<Minute>)]) -> String {
+        let mut data = String::new();
+
+        for (i, (id, minutes)) in shifts.iter().enumerate() {
+            let date = format!("1518-{:02}-{:02}", 1 + i / 28, 1 + i % 28);
+            data.push_str(&format!("[{} 00:00] Guard #{} begins shift\n", date, id));
+
+            for session in minutes.chunks(2) {
+                data.push_str(&format!("[{} 00:{:02}] falls asleep\n", date, session[0]));
+                data.push_str(&format!("[{} 00:{:02}] wakes up\n", date, session[1]));
+            }
+        }
+        data
+    }
 
     const DATA: &str = r#"
 [1518-11-01 00:00] Guard #10 begins shift
@@ -200,12 +311,100 @@ mod tests {
     #[test]
     fn test_part1() {
         let input = get_input(DATA.as_bytes()).unwrap();
-        assert_eq!(240, part1(&input));
+        assert_eq!(240, part1(&input).unwrap());
     }
 
     #[test]
     fn test_part2() {
         let input = get_input(DATA.as_bytes()).unwrap();
-        assert_eq!(4455, part2(&input));
+        assert_eq!(4455, part2(&input).unwrap());
+    }
+
+    #[test]
+    fn test_get_input_malformed() {
+        let data = "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] dozes off\n";
+        match get_input(data.as_bytes()) {
+            Err(Error::Malformed { line, text, .. }) => {
+                assert_eq!(2, line);
+                assert_eq!("[1518-11-01 00:05] dozes off", text);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_no_sleep() {
+        let input = get_input("".as_bytes()).unwrap();
+        match (part1(&input), part2(&input)) {
+            (Err(Error::NoSolution(_)), Err(Error::NoSolution(_))) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let input = get_input("[1518-11-01 00:00] Guard #10 begins shift\n".as_bytes()).unwrap();
+        match part1(&input) {
+            Err(Error::NoSolution(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_wakes_up_before_sleep() {
+        let data = "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] wakes up\n";
+        let input = get_input(data.as_bytes()).unwrap();
+        match part1(&input) {
+            Err(Error::Malformed { line, text, .. }) => {
+                assert_eq!(2, line);
+                assert_eq!("[1518-11-01 00:05] wakes up", text);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // falls asleep one night, then wakes up at an earlier minute the next one
+        let data = "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:50] falls asleep\n[1518-11-02 00:10] wakes up\n";
+        let input = get_input(data.as_bytes()).unwrap();
+        match part2(&input) {
+            Err(Error::Malformed { line, .. }) => assert_eq!(3, line),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_get_input_inconsistent() {
+        // a second shift at the same minute would take over the first guard's sleep
+        let data = "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] falls asleep\n[1518-11-01 00:00] Guard #99 begins shift\n";
+        match get_input(data.as_bytes()) {
+            Err(Error::Malformed { line, text, .. }) => {
+                assert_eq!(3, line);
+                assert_eq!("[1518-11-01 00:00] Guard #99 begins shift", text);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // guards may start their shift before midnight, but never sleep then
+        let data = "[1518-11-01 23:58] Guard #10 begins shift\n[1518-11-02 00:05] falls asleep\n[1518-11-02 01:25] wakes up\n";
+        match get_input(data.as_bytes()) {
+            Err(Error::Malformed { line, .. }) => assert_eq!(3, line),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_part1_matches_naive(shifts in shifts()) {
+            prop_assume!(shifts.iter().any(|(_, minutes)| !minutes.is_empty()));
+
+            let input = get_input(to_log(&shifts).as_bytes()).unwrap();
+            let answers = naive_answers(&naive_sleep(&shifts), |count| count.iter().sum());
+            prop_assert!(answers.contains(&part1(&input).unwrap()));
+        }
+
+        #[test]
+        fn prop_part2_matches_naive(shifts in shifts()) {
+            prop_assume!(shifts.iter().any(|(_, minutes)| !minutes.is_empty()));
+
+            let input = get_input(to_log(&shifts).as_bytes()).unwrap();
+            let answers = naive_answers(&naive_sleep(&shifts), |count| *count.iter().max().unwrap());
+            prop_assert!(answers.contains(&part2(&input).unwrap()));
+        }
     }
 }
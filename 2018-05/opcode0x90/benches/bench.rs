@@ -1,27 +1,22 @@
 #[macro_use]
 extern crate criterion;
-extern crate aoc;
+extern crate aoc_2018_05;
+extern crate aoc_bench;
+extern crate rand;
 
-use std::fs::File;
+mod synthetic;
 
-use aoc::*;
+use aoc_2018_05::Day05;
+use aoc_bench::{bench, bench_synthetic};
 use criterion::Criterion;
 
-fn bench_part1(c: &mut Criterion) {
-    c.bench_function("part1", |b| {
-        let f = File::open("input.txt").expect("input.txt not found!");
-        let input = get_input(f).unwrap();
-        b.iter(|| part1(&input))
-    });
+fn bench_day05(c: &mut Criterion) {
+    bench::<Day05>(c);
 }
 
-fn bench_part2(c: &mut Criterion) {
-    c.bench_function("part2", |b| {
-        let f = File::open("input.txt").expect("input.txt not found!");
-        let input = get_input(f).unwrap();
-        b.iter(|| part2(&input))
-    });
+fn bench_day05_synthetic(c: &mut Criterion) {
+    bench_synthetic::<Day05>(c, &[1, 10, 100], synthetic::generate);
 }
 
-criterion_group!(benches, bench_part1, bench_part2);
+criterion_group!(benches, bench_day05, bench_day05_synthetic);
 criterion_main!(benches);
@@ -0,0 +1,20 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// number of units in input.txt
+const UNITS: usize = 50000;
+
+/// generate a random polymer `scale` times as long as input.txt
+pub fn generate(scale: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(2018);
+
+    (0..UNITS * scale)
+        .map(|_| {
+            let unit = rng.gen_range(b'a', b'z' + 1) as char;
+            if rng.gen() {
+                unit.to_ascii_uppercase()
+            } else {
+                unit
+            }
+        }).collect()
+}
@@ -1,21 +1,23 @@
+extern crate aoc_common;
 extern crate rayon;
 
 use std::collections::HashSet;
-use std::error::Error;
 use std::io::{BufReader, Read};
 use std::iter::FromIterator;
 
+use aoc_common::{Error, Solution};
 use rayon::prelude::*;
 
-pub fn part1(input: &String) -> usize {
+/// fully react the polymer, returns the remaining units
+pub fn react(input: &str) -> String {
     // simulate polymer reaction
-    let result = input
+    input
         .chars()
         .fold(String::with_capacity(input.len()), |mut buf, c| {
             let tail = buf.chars().last().unwrap_or('!');
 
             // is this reactive?
-            if c.to_ascii_lowercase() == tail.to_ascii_lowercase()
+            if c.eq_ignore_ascii_case(&tail)
                 && ((c.is_ascii_lowercase() && tail.is_ascii_uppercase())
                     || (c.is_ascii_uppercase() && tail.is_ascii_lowercase()))
             {
@@ -27,13 +29,15 @@ pub fn part1(input: &String) -> usize {
             }
             // println!("[{}, {}] {}", tail, c, buf);
             buf
-        });
+        })
+}
 
+pub fn part1(input: &str) -> usize {
     // count the remaining units in polymer
-    result.len()
+    react(input).len()
 }
 
-pub fn part2(input: &String) -> usize {
+pub fn part2(input: &str) -> Result<usize, Error> {
     // find out all available units
     let units: HashSet<char> = HashSet::from_iter(input.chars());
 
@@ -48,10 +52,10 @@ pub fn part2(input: &String) -> usize {
             // run the simulation
             part1(&buf)
         }).min()
-        .expect("there is no solution!")
+        .ok_or(Error::NoSolution("the polymer has no units to remove"))
 }
 
-pub fn get_input(f: impl Read) -> Result<String, Box<Error>> {
+pub fn get_input(f: impl Read) -> Result<String, Error> {
     // read data from input.txt
     let mut buf = String::new();
     BufReader::new(f).read_to_string(&mut buf)?;
@@ -61,9 +65,59 @@ pub fn get_input(f: impl Read) -> Result<String, Box<Error>> {
     Ok(input)
 }
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    const YEAR: u32 = 2018;
+    const DAY: u32 = 5;
+
+    type Input = String;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(f: impl Read) -> Result<String, Error> {
+        get_input(f)
+    }
+
+    fn part1(input: &String) -> Result<usize, Error> {
+        Ok(part1(input))
+    }
+
+    fn part2(input: &String) -> Result<usize, Error> {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    fn reacts(a: char, b: char) -> bool {
+        a != b && a.eq_ignore_ascii_case(&b)
+    }
+
+    /// naive reference implementation of part1, removes one pair per scan
+    fn naive_part1(input: &str) -> usize {
+        let mut units = input.chars().collect::<Vec<_>>();
+
+        while let Some(i) = (1..units.len()).find(|&i| reacts(units[i - 1], units[i])) {
+            units.drain(i - 1..=i);
+        }
+        units.len()
+    }
+
+    /// naive reference implementation of part2, tries every letter a to z
+    fn naive_part2(input: &str) -> usize {
+        (b'a'..=b'z')
+            .map(|unit| unit as char)
+            .filter(|unit| input.contains(|c: char| c.eq_ignore_ascii_case(unit)))
+            .map(|unit| {
+                let polymer = input.replace([unit, unit.to_ascii_uppercase()], "");
+                naive_part1(&polymer)
+            }).min()
+            .unwrap()
+    }
 
     #[test]
     fn test_part1() {
@@ -72,6 +126,39 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        assert_eq!(4, part2(&String::from("dabAcCaCBAcCcaDA")));
+        assert_eq!(4, part2(&String::from("dabAcCaCBAcCcaDA")).unwrap());
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(0, part1(""));
+        match part2("") {
+            Err(Error::NoSolution(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_part1_keeps_parity(input in "[a-cA-C]{0,40}") {
+            prop_assert_eq!(input.len() % 2, part1(&input) % 2);
+        }
+
+        #[test]
+        fn prop_react_is_idempotent(input in "[a-cA-C]{0,40}") {
+            let polymer = react(&input);
+            prop_assert_eq!(&polymer, &react(&polymer));
+            prop_assert_eq!(polymer.len(), part1(&polymer));
+        }
+
+        #[test]
+        fn prop_part1_matches_naive(input in "[a-cA-C]{0,40}") {
+            prop_assert_eq!(naive_part1(&input), part1(&input));
+        }
+
+        #[test]
+        fn prop_part2_matches_naive(input in "[a-cA-C]{1,40}") {
+            prop_assert_eq!(naive_part2(&input), part2(&input).unwrap());
+        }
     }
 }
@@ -0,0 +1,85 @@
+extern crate aoc_common;
+extern crate criterion;
+
+use std::fmt;
+use std::fs;
+use std::rc::Rc;
+
+use aoc_common::Solution;
+use criterion::{Benchmark, Criterion, ParameterizedBenchmark, Throughput};
+
+/// bench parsing, both parts and the whole run of a day over its input.txt
+pub fn bench<S: Solution + 'static>(c: &mut Criterion) {
+    // read data from input.txt
+    let data = Rc::new(fs::read("input.txt").expect("input.txt not found!"));
+    let input = Rc::new(S::parse(&data[..]).unwrap());
+
+    let benchmark = Benchmark::new("parse", {
+        let data = data.clone();
+        move |b| b.iter(|| S::parse(&data[..]).unwrap())
+    }).with_function("part1", {
+        let input = input.clone();
+        move |b| b.iter(|| S::part1(&input).unwrap())
+    }).with_function("part2", {
+        let input = input.clone();
+        move |b| b.iter(|| S::part2(&input).unwrap())
+    }).with_function("total", {
+        let data = data.clone();
+        move |b| {
+            b.iter(|| {
+                let input = S::parse(&data[..]).unwrap();
+                (S::part1(&input).unwrap(), S::part2(&input).unwrap())
+            })
+        }
+    }).throughput(Throughput::Bytes(data.len() as u32));
+
+    c.bench(&format!("{}-{:02}", S::YEAR, S::DAY), benchmark);
+}
+
+/// synthetic input at given multiple of the puzzle input size
+pub struct Scaled<I> {
+    pub scale: usize,
+    pub data: Vec<u8>,
+    pub input: I,
+}
+
+impl<I> fmt::Debug for Scaled<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x", self.scale)
+    }
+}
+
+/// generate and parse a synthetic input at every given scale
+pub fn scaled<S: Solution>(
+    scales: &[usize],
+    generate: impl Fn(usize) -> String,
+) -> Vec<Scaled<S::Input>> {
+    scales
+        .iter()
+        .map(|&scale| {
+            let data = generate(scale).into_bytes();
+            let input = S::parse(&data[..]).unwrap();
+            Scaled { scale, data, input }
+        }).collect()
+}
+
+/// bench parsing and both parts of a day over synthetic inputs at every given scale
+pub fn bench_synthetic<S: Solution + 'static>(
+    c: &mut Criterion,
+    scales: &[usize],
+    generate: impl Fn(usize) -> String,
+) {
+    // generate the inputs upfront, so they are shared by every function
+    let inputs = scaled::<S>(scales, generate);
+
+    let benchmark = ParameterizedBenchmark::new(
+        "parse",
+        |b, s: &Scaled<S::Input>| b.iter(|| S::parse(&s.data[..]).unwrap()),
+        inputs,
+    ).with_function("part1", |b, s| b.iter(|| S::part1(&s.input).unwrap()))
+    .with_function("part2", |b, s| b.iter(|| S::part2(&s.input).unwrap()))
+    .throughput(|s| Throughput::Bytes(s.data.len() as u32))
+    .sample_size(10);
+
+    c.bench(&format!("{}-{:02}/synthetic", S::YEAR, S::DAY), benchmark);
+}
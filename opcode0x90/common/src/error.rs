@@ -0,0 +1,92 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::num::ParseIntError;
+
+/// error encountered while parsing or solving the puzzle
+#[derive(Debug)]
+pub enum Error {
+    /// unable to read the input
+    Io(io::Error),
+    /// unable to parse an integer, `line` is 1-based
+    ParseInt {
+        line: usize,
+        text: String,
+        source: ParseIntError,
+    },
+    /// line does not follow the expected format, `line` is 1-based
+    Malformed {
+        line: usize,
+        text: String,
+        reason: &'static str,
+    },
+    /// some lines are inconsistent with the rest of the input, `lines` are 1-based
+    Inconsistent {
+        lines: Vec<usize>,
+        reason: &'static str,
+    },
+    /// the puzzle input has no solution
+    NoSolution(&'static str),
+    /// arithmetic overflow, with the name of the type that overflowed
+    Overflow(&'static str),
+}
+
+impl Error {
+    pub fn parse_int(line: usize, text: &str, source: ParseIntError) -> Error {
+        Error::ParseInt {
+            line,
+            text: String::from(text),
+            source,
+        }
+    }
+
+    pub fn malformed(line: usize, text: &str, reason: &'static str) -> Error {
+        Error::Malformed {
+            line,
+            text: String::from(text),
+            reason,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "unable to read input: {}", err),
+            Error::ParseInt { line, text, source } => {
+                write!(f, "line {}: invalid integer {:?}: {}", line, text, source)
+            }
+            Error::Malformed { line, text, reason } => {
+                write!(f, "line {}: {}: {:?}", line, reason, text)
+            }
+            Error::Inconsistent { lines, reason } => {
+                let lines = lines.iter().map(usize::to_string).collect::<Vec<_>>();
+                match lines.len() {
+                    1 => write!(f, "line {}: {}", lines[0], reason),
+                    _ => write!(f, "lines {}: {}", lines.join(", "), reason),
+                }
+            }
+            Error::NoSolution(reason) => write!(f, "no solution: {}", reason),
+            Error::Overflow(ty) => write!(f, "value does not fit in {}", ty),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::ParseInt { source, .. } => Some(source),
+            Error::Malformed { .. }
+            | Error::Inconsistent { .. }
+            | Error::NoSolution(_)
+            | Error::Overflow(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
@@ -0,0 +1,33 @@
+mod error;
+
+use std::fmt::Display;
+use std::io::Read;
+
+pub use crate::error::Error;
+
+/// common interface implemented by the solution of every day
+pub trait Solution {
+    /// year of the puzzle
+    const YEAR: u32;
+    /// day of the puzzle
+    const DAY: u32;
+
+    /// parsed puzzle input shared by both parts
+    type Input;
+    /// answer of part 1
+    type Part1: Display;
+    /// answer of part 2
+    type Part2: Display;
+
+    /// parse the puzzle input from given reader
+    fn parse(f: impl Read) -> Result<Self::Input, Error>;
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error>;
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error>;
+
+    /// extra details about how the answers were found, if any
+    fn details(_input: &Self::Input) -> Result<Option<String>, Error> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,374 @@
+extern crate aoc_common;
+extern crate clap;
+extern crate serde;
+extern crate serde_json;
+
+extern crate aoc_2018_01;
+extern crate aoc_2018_02;
+extern crate aoc_2018_03;
+extern crate aoc_2018_04;
+extern crate aoc_2018_05;
+
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, Instant};
+
+use aoc_2018_01::Day01;
+use aoc_2018_02::{BkTree, Day02, Distance};
+use aoc_2018_03::Day03;
+use aoc_2018_04::Day04;
+use aoc_2018_05::Day05;
+use aoc_common::Solution;
+use clap::{App, Arg};
+use serde::Serialize;
+
+/// every (year, day) that has a solution in this workspace
+const DAYS: &[(u32, u32)] = &[(2018, 1), (2018, 2), (2018, 3), (2018, 4), (2018, 5)];
+
+/// directory containing the solution (and its input.txt) for given day
+fn day_dir(year: u32, day: u32) -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "..",
+        "..",
+        &format!("{}-{:02}", year, day),
+        "opcode0x90",
+    ]
+        .iter()
+        .collect()
+}
+
+/// path to the puzzle input of given day, `AOC_INPUT_DIR` takes precedence if set
+fn input_path(year: u32, day: u32) -> PathBuf {
+    match env::var_os("AOC_INPUT_DIR") {
+        Some(dir) => PathBuf::from(dir).join(format!("{}-{:02}.txt", year, day)),
+        None => day_dir(year, day).join("input.txt"),
+    }
+}
+
+/// path to the expected answers of given day, `AOC_INPUT_DIR` takes precedence if set
+fn answers_path(year: u32, day: u32) -> PathBuf {
+    match env::var_os("AOC_INPUT_DIR") {
+        Some(dir) => PathBuf::from(dir).join(format!("{}-{:02}.answers.txt", year, day)),
+        None => day_dir(year, day).join("answers.txt"),
+    }
+}
+
+/// read the expected answers of given day, keyed by part
+fn get_answers(year: u32, day: u32) -> Result<HashMap<u32, String>, Box<dyn Error>> {
+    let path = answers_path(year, day);
+    let data = fs::read_to_string(&path)
+        .map_err(|err| format!("unable to open {}: {}", path.display(), err))?;
+
+    let mut answers = HashMap::new();
+
+    for line in data.lines().filter(|line| !line.trim().is_empty()) {
+        // each line is formatted as `part<N>: <answer>`
+        let malformed = || format!("{}: malformed answer {:?}", path.display(), line);
+        let i = line.find(':').ok_or_else(malformed)?;
+        let part = line[..i]
+            .trim()
+            .trim_start_matches("part")
+            .parse::<u32>()
+            .map_err(|_| malformed())?;
+
+        answers.insert(part, String::from(line[i + 1..].trim()));
+    }
+    Ok(answers)
+}
+
+#[derive(PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// command line options shared by every day
+struct Options {
+    part: Option<u32>,
+    input: Option<String>,
+    format: Format,
+    check: bool,
+    details: bool,
+}
+
+/// result of running one part of a puzzle, as printed by `--format json`
+#[derive(Serialize)]
+struct Report {
+    year: u32,
+    day: u32,
+    part: u32,
+    answer: String,
+    parse_time_ns: u64,
+    solve_time_ns: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    correct: Option<bool>,
+}
+
+/// extra details about how the answers were found, as printed by `--format json`
+#[derive(Serialize)]
+struct Details {
+    year: u32,
+    day: u32,
+    details: String,
+}
+
+fn nanos(d: Duration) -> u64 {
+    d.as_secs() * 1_000_000_000 + u64::from(d.subsec_nanos())
+}
+
+/// print the answer of given part, returns false if it does not match `expected`
+fn report<S: Solution>(
+    opts: &Options,
+    part: u32,
+    answer: impl Display,
+    expected: Option<&String>,
+    parse_time: Duration,
+    solve_time: Duration,
+) -> Result<bool, Box<dyn Error>> {
+    let answer = answer.to_string();
+    let correct = expected.map(|expected| *expected == answer);
+
+    match opts.format {
+        Format::Text => {
+            println!("part{}: {}", part, answer);
+
+            if let (Some(false), Some(expected)) = (correct, expected) {
+                eprintln!(
+                    "{}-{:02} part{}: expected {}, got {}",
+                    S::YEAR,
+                    S::DAY,
+                    part,
+                    expected,
+                    answer
+                );
+            }
+        }
+        Format::Json => {
+            let report = Report {
+                year: S::YEAR,
+                day: S::DAY,
+                part,
+                answer,
+                parse_time_ns: nanos(parse_time),
+                solve_time_ns: nanos(solve_time),
+                correct,
+            };
+            println!("{}", serde_json::to_string(&report)?);
+        }
+    }
+    Ok(correct != Some(false))
+}
+
+/// open the puzzle input of given day from stdin, given path or input.txt
+fn open_input(opts: &Options, year: u32, day: u32) -> Result<Box<dyn Read>, Box<dyn Error>> {
+    let path = match opts.input {
+        Some(ref path) => PathBuf::from(path),
+        None => input_path(year, day),
+    };
+    if path.to_str() == Some("-") {
+        return Ok(Box::new(io::stdin()));
+    }
+    let f = File::open(&path).map_err(|err| format!("unable to open {}: {}", path.display(), err))?;
+    Ok(Box::new(f))
+}
+
+/// run the solution of given day, returns false if any answer is incorrect
+fn solve<S: Solution>(opts: &Options) -> Result<bool, Box<dyn Error>> {
+    // read data from stdin, given path or input.txt
+    let f = open_input(opts, S::YEAR, S::DAY)?;
+    let start = Instant::now();
+    let input = S::parse(f)?;
+    let parse_time = start.elapsed();
+
+    let answers = if opts.check {
+        get_answers(S::YEAR, S::DAY)?
+    } else {
+        HashMap::new()
+    };
+    let expected = |part| -> Result<Option<&String>, Box<dyn Error>> {
+        match answers.get(&part) {
+            None if opts.check => Err(format!(
+                "there is no recorded answer for {}-{:02} part{}!",
+                S::YEAR,
+                S::DAY,
+                part
+            ).into()),
+            answer => Ok(answer),
+        }
+    };
+    let mut correct = true;
+
+    if opts.part != Some(2) {
+        let start = Instant::now();
+        let answer = S::part1(&input)?;
+        let elapsed = start.elapsed();
+        correct &= report::<S>(opts, 1, answer, expected(1)?, parse_time, elapsed)?;
+    }
+    if opts.part != Some(1) {
+        let start = Instant::now();
+        let answer = S::part2(&input)?;
+        let elapsed = start.elapsed();
+        correct &= report::<S>(opts, 2, answer, expected(2)?, parse_time, elapsed)?;
+    }
+    if opts.details {
+        if let Some(details) = S::details(&input)? {
+            match opts.format {
+                Format::Text => println!("details: {}", details),
+                Format::Json => {
+                    let details = Details {
+                        year: S::YEAR,
+                        day: S::DAY,
+                        details,
+                    };
+                    println!("{}", serde_json::to_string(&details)?);
+                }
+            }
+        }
+    }
+    Ok(correct)
+}
+
+fn run(year: u32, day: u32, opts: &Options) -> Result<bool, Box<dyn Error>> {
+    match (year, day) {
+        (2018, 1) => solve::<Day01>(opts),
+        (2018, 2) => solve::<Day02>(opts),
+        (2018, 3) => solve::<Day03>(opts),
+        (2018, 4) => solve::<Day04>(opts),
+        (2018, 5) => solve::<Day05>(opts),
+        _ => Err(format!("there is no solution for {}-{:02}!", year, day).into()),
+    }
+}
+
+/// answer near-match queries on the box IDs of 2018-02, read from stdin
+fn repl(year: u32, day: u32, opts: &Options, distance: Distance) -> Result<(), Box<dyn Error>> {
+    if (year, day) != (2018, 2) {
+        return Err(format!("there is no REPL for {}-{:02}!", year, day).into());
+    }
+    if opts.input.as_deref() == Some("-") {
+        return Err("the REPL reads its commands from stdin, the input has to be a file!".into());
+    }
+
+    let ids = aoc_2018_02::get_input_ragged(open_input(opts, year, day)?)?;
+    let tree = BkTree::new(ids, distance);
+    let stdin = io::stdin();
+    aoc_2018_02::repl(&tree, stdin.lock(), io::stdout())?;
+    Ok(())
+}
+
+fn try_main() -> Result<(), Box<dyn Error>> {
+    let matches = App::new("aoc")
+        .about("Runs Advent of Code solutions")
+        .arg(
+            Arg::with_name("year")
+                .long("year")
+                .takes_value(true)
+                .default_value("2018"),
+        ).arg(
+            Arg::with_name("day")
+                .long("day")
+                .takes_value(true)
+                .help("runs every day of the year if omitted"),
+        ).arg(
+            Arg::with_name("part")
+                .long("part")
+                .takes_value(true)
+                .possible_values(&["1", "2"])
+                .help("runs both parts if omitted"),
+        ).arg(
+            Arg::with_name("input")
+                .requires("day")
+                .help("path to the puzzle input, or - for stdin"),
+        ).arg(
+            Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["text", "json"])
+                .default_value("text")
+                .help("json prints one object per line with answer and timings"),
+        ).arg(
+            Arg::with_name("check")
+                .long("check")
+                .conflicts_with("input")
+                .help("compares the answers against answers.txt, fails on mismatch"),
+        ).arg(
+            Arg::with_name("details")
+                .long("details")
+                .help("prints how the answers were found, for days that support it"),
+        ).arg(
+            Arg::with_name("repl")
+                .long("repl")
+                .requires("day")
+                .conflicts_with_all(&["check", "part", "details"])
+                .help("queries the input interactively, for days that support it"),
+        ).arg(
+            Arg::with_name("distance")
+                .long("distance")
+                .takes_value(true)
+                .possible_values(&["hamming", "levenshtein"])
+                .default_value("levenshtein")
+                .help("distance between IDs used by the 2018-02 REPL"),
+        ).get_matches();
+
+    let year: u32 = matches.value_of("year").unwrap_or("2018").parse()?;
+    let day = match matches.value_of("day") {
+        Some(day) => Some(day.parse::<u32>()?),
+        None => None,
+    };
+    let opts = Options {
+        part: match matches.value_of("part") {
+            Some(part) => Some(part.parse::<u32>()?),
+            None => None,
+        },
+        input: matches.value_of("input").map(String::from),
+        format: match matches.value_of("format") {
+            Some("json") => Format::Json,
+            _ => Format::Text,
+        },
+        check: matches.is_present("check"),
+        details: matches.is_present("details"),
+    };
+
+    if matches.is_present("repl") {
+        let distance = match matches.value_of("distance") {
+            Some("hamming") => Distance::Hamming,
+            _ => Distance::Levenshtein,
+        };
+        // --repl requires --day
+        return repl(year, day.unwrap_or(0), &opts, distance);
+    }
+
+    let correct = match day {
+        Some(day) => run(year, day, &opts)?,
+        None => {
+            let mut correct = true;
+
+            // run every day of the given year
+            for &(y, d) in DAYS.iter().filter(|&&(y, _)| y == year) {
+                if opts.format == Format::Text {
+                    println!("{}-{:02}", y, d);
+                }
+                correct &= run(y, d, &opts)?;
+            }
+            correct
+        }
+    };
+
+    if !correct {
+        return Err("some answers are incorrect!".into());
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = try_main() {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}